@@ -0,0 +1,19 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mcumgr_smp::transport::smp_framing::SmpTransportDecoder;
+
+fuzz_target!(|data: &[u8]| {
+    let mut decoder = SmpTransportDecoder::new();
+    for line in data.split_inclusive(|&b| b == b'\n') {
+        match decoder.input_line(line) {
+            Ok(true) => {
+                let _ = std::mem::take(&mut decoder).into_frame_payload();
+            }
+            Ok(false) => {}
+            Err(_) => decoder = SmpTransportDecoder::new(),
+        }
+    }
+});
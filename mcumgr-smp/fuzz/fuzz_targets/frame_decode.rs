@@ -0,0 +1,15 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mcumgr_smp::application_management::GetImageStateResult;
+use mcumgr_smp::codec::RawCodec;
+use mcumgr_smp::os_management::EchoResult;
+use mcumgr_smp::SmpFrame;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = SmpFrame::<Vec<u8>>::decode::<RawCodec>(data);
+    let _ = SmpFrame::<EchoResult>::decode_with_cbor(data);
+    let _ = SmpFrame::<GetImageStateResult>::decode_with_cbor(data);
+});
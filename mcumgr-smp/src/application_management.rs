@@ -1,8 +1,11 @@
 // Author: Sascha Zenglein <zenglein@gessler.de>
 // Copyright (c) 2023 Gessler GmbH.
 
-use crate::{Group, OpCode, SmpFrame};
+use crate::response::SmpResponse;
+use crate::{Group, OpCode, SmpError, SmpFrame, SmpRequest};
 
+use alloc::string::String;
+use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 
 pub enum ApplicationManagementCommand {
@@ -12,24 +15,36 @@ pub enum ApplicationManagementCommand {
     Unknown(u8),
 }
 
-impl From<ApplicationManagementCommand> for u8 {
-    fn from(cmd: ApplicationManagementCommand) -> Self {
-        match cmd {
+impl ApplicationManagementCommand {
+    pub const fn id(&self) -> u8 {
+        match self {
             ApplicationManagementCommand::State => 0,
             ApplicationManagementCommand::Upload => 1,
             ApplicationManagementCommand::Erase => 5,
-            ApplicationManagementCommand::Unknown(n) => n,
+            ApplicationManagementCommand::Unknown(n) => *n,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
-pub enum GetImageStateResult {
-    Ok(GetImageStatePayload),
-    Err(GetImageStateError),
+impl From<ApplicationManagementCommand> for u8 {
+    fn from(cmd: ApplicationManagementCommand) -> Self {
+        cmd.id()
+    }
 }
 
+impl From<u8> for ApplicationManagementCommand {
+    fn from(id: u8) -> Self {
+        match id {
+            0 => ApplicationManagementCommand::State,
+            1 => ApplicationManagementCommand::Upload,
+            5 => ApplicationManagementCommand::Erase,
+            n => ApplicationManagementCommand::Unknown(n),
+        }
+    }
+}
+
+pub type GetImageStateResult = SmpResponse<GetImageStatePayload>;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetImageStatePayload {
     pub images: Vec<ImageState>,
@@ -37,13 +52,6 @@ pub struct GetImageStatePayload {
     pub split_status: Option<i32>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct GetImageStateError {
-    pub rc: i32,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub rsn: Option<String>,
-}
-
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ImageState {
     pub image: Option<i32>,
@@ -66,35 +74,34 @@ pub struct ImageState {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetStatePayload {}
 
+impl SmpRequest for GetStatePayload {
+    const OPERATION: OpCode = OpCode::ReadRequest;
+    const GROUP: Group = Group::ApplicationManagement;
+    const COMMAND: u8 = ApplicationManagementCommand::State.id();
+    type Response = GetImageStatePayload;
+}
+
 pub fn get_state(sequence: u8) -> SmpFrame<GetStatePayload> {
-    SmpFrame {
-        operation: OpCode::ReadRequest,
-        flags: 0,
-        group: Group::ApplicationManagement,
-        sequence,
-        command: ApplicationManagementCommand::State.into(),
-        data: GetStatePayload {},
-    }
+    GetStatePayload {}.into_frame(sequence)
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SetStatePayload {
-    #[serde(with = "serde_bytes")]
+    /// may be empty when confirming the running image
+    #[serde(with = "serde_bytes", default)]
     pub hash: Vec<u8>,
     pub confirm: bool,
 }
 
+impl SmpRequest for SetStatePayload {
+    const OPERATION: OpCode = OpCode::WriteRequest;
+    const GROUP: Group = Group::ApplicationManagement;
+    const COMMAND: u8 = ApplicationManagementCommand::State.id();
+    type Response = GetImageStatePayload;
+}
+
 pub fn set_state(hash: Vec<u8>, confirm: bool, sequence: u8) -> SmpFrame<SetStatePayload> {
-    let data = SetStatePayload { hash, confirm };
-
-    SmpFrame {
-        operation: OpCode::WriteRequest,
-        flags: 0,
-        group: Group::ApplicationManagement,
-        sequence,
-        command: ApplicationManagementCommand::State.into(),
-        data,
-    }
+    SetStatePayload { hash, confirm }.into_frame(sequence)
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -113,6 +120,37 @@ pub struct ImageChunk<'d, 's> {
     pub upgrade: Option<bool>,
 }
 
+impl SmpRequest for ImageChunk<'_, '_> {
+    const OPERATION: OpCode = OpCode::WriteRequest;
+    const GROUP: Group = Group::ApplicationManagement;
+    const COMMAND: u8 = ApplicationManagementCommand::Upload.id();
+    type Response = WriteImageChunkPayload;
+}
+
+/// Owned version of [ImageChunk], used to decode upload requests
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ImageUploadRequest {
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+    pub off: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub len: Option<usize>,
+    #[serde(with = "serde_bytes", default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha: Option<Vec<u8>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upgrade: Option<bool>,
+}
+
+impl SmpRequest for ImageUploadRequest {
+    const OPERATION: OpCode = OpCode::WriteRequest;
+    const GROUP: Group = Group::ApplicationManagement;
+    const COMMAND: u8 = ApplicationManagementCommand::Upload.id();
+    type Response = WriteImageChunkPayload;
+}
+
 pub struct ImageWriter<'s> {
     pub image: Option<u8>,
     pub hash: Option<&'s [u8]>,
@@ -123,7 +161,12 @@ pub struct ImageWriter<'s> {
 }
 
 impl ImageWriter<'_> {
-    pub fn new(image: Option<u8>, len: usize, hash: Option<&[u8]>, upgrade: bool) -> ImageWriter {
+    pub fn new(
+        image: Option<u8>,
+        len: usize,
+        hash: Option<&[u8]>,
+        upgrade: bool,
+    ) -> ImageWriter<'_> {
         ImageWriter {
             image,
             hash,
@@ -166,23 +209,24 @@ impl ImageWriter<'_> {
 
         (self.sequence, _) = self.sequence.overflowing_add(1);
 
-        SmpFrame::new(
-            OpCode::WriteRequest,
-            self.sequence,
-            Group::ApplicationManagement,
-            ApplicationManagementCommand::Upload.into(),
-            chunk_data,
-        )
+        chunk_data.into_frame(self.sequence)
     }
-}
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
-pub enum WriteImageChunkResult {
-    Ok(WriteImageChunkPayload),
-    Err(WriteImageChunkError),
+    /// Encode the next chunk into `buf` without allocating and return the length of the frame.  
+    /// If the frame doesn't fit, [SmpError::BufferTooSmall] is returned and the writer
+    /// is left unchanged, so the chunk can be retried with less data.
+    pub fn write_chunk_into(&mut self, data: &[u8], buf: &mut [u8]) -> Result<usize, SmpError> {
+        let (offset, sequence) = (self.offset, self.sequence);
+        let result = self.write_chunk(data).encode_into_with_cbor(buf);
+        if result.is_err() {
+            (self.offset, self.sequence) = (offset, sequence);
+        }
+        result
+    }
 }
 
+pub type WriteImageChunkResult = SmpResponse<WriteImageChunkPayload>;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct WriteImageChunkPayload {
     pub off: u32,
@@ -192,8 +236,24 @@ pub struct WriteImageChunkPayload {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-pub struct WriteImageChunkError {
-    pub rc: i32,
+pub struct EraseRequest {
+    /// slot to erase, defaults to the inactive slot
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub rsn: Option<String>,
+    pub slot: Option<u32>,
+}
+
+impl SmpRequest for EraseRequest {
+    const OPERATION: OpCode = OpCode::WriteRequest;
+    const GROUP: Group = Group::ApplicationManagement;
+    const COMMAND: u8 = ApplicationManagementCommand::Erase.id();
+    type Response = EraseResponse;
+}
+
+pub fn erase(slot: Option<u32>, sequence: u8) -> SmpFrame<EraseRequest> {
+    EraseRequest { slot }.into_frame(sequence)
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EraseResponse {}
+
+pub type EraseResult = SmpResponse<EraseResponse>;
@@ -0,0 +1,164 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+/// Error returned by a payload codec
+pub type CodecError = Box<dyn core::error::Error + Send + Sync>;
+
+/// Returned by [EncodePayload::encode_payload_into] if the payload doesn't fit into the buffer
+#[derive(thiserror::Error, Debug)]
+#[error("buffer too small")]
+pub struct BufferTooSmall;
+
+/// A serialization format for SMP payloads.
+///
+/// Codecs are stateless marker types. [EncodePayload] and [DecodePayload] are implemented
+/// for the payload types a codec supports, which allows a codec to encode types
+/// it can't decode (e.g. borrowed request data) and vice versa.
+pub trait PayloadCodec {
+    /// Short name of the format, e.g. for debug output
+    const NAME: &'static str;
+}
+
+/// Encode payloads of type `T`
+pub trait EncodePayload<T: ?Sized>: PayloadCodec {
+    /// Append the encoded payload to `buf`
+    fn encode_payload(data: &T, buf: &mut Vec<u8>) -> Result<(), CodecError>;
+
+    /// Encode the payload into `buf` and return the number of bytes written.  
+    /// Fails with [BufferTooSmall] if the payload doesn't fit.
+    ///
+    /// The default implementation encodes into a temporary [Vec],
+    /// codecs that can write in place should override it.
+    fn encode_payload_into(data: &T, buf: &mut [u8]) -> Result<usize, CodecError> {
+        let mut payload = Vec::new();
+        Self::encode_payload(data, &mut payload)?;
+        buf.get_mut(..payload.len())
+            .ok_or(BufferTooSmall)?
+            .copy_from_slice(&payload);
+        Ok(payload.len())
+    }
+
+    /// Write the encoded payload to `writer`.
+    ///
+    /// The default implementation encodes into a temporary [Vec],
+    /// codecs that can stream should override it.
+    #[cfg(feature = "std")]
+    fn write_payload<W: std::io::Write>(data: &T, mut writer: W) -> Result<(), CodecError> {
+        let mut payload = Vec::new();
+        Self::encode_payload(data, &mut payload)?;
+        writer.write_all(&payload)?;
+        Ok(())
+    }
+}
+
+/// Decode payloads of type `T`
+pub trait DecodePayload<T>: PayloadCodec {
+    fn decode_payload(buf: &[u8]) -> Result<T, CodecError>;
+}
+
+/// Passes the payload through as raw bytes, for payloads with a custom encoding.
+pub struct RawCodec;
+
+impl PayloadCodec for RawCodec {
+    const NAME: &'static str = "raw";
+}
+
+impl<T: AsRef<[u8]> + ?Sized> EncodePayload<T> for RawCodec {
+    fn encode_payload(data: &T, buf: &mut Vec<u8>) -> Result<(), CodecError> {
+        buf.extend_from_slice(data.as_ref());
+        Ok(())
+    }
+
+    fn encode_payload_into(data: &T, buf: &mut [u8]) -> Result<usize, CodecError> {
+        let data = data.as_ref();
+        buf.get_mut(..data.len())
+            .ok_or(BufferTooSmall)?
+            .copy_from_slice(data);
+        Ok(data.len())
+    }
+
+    #[cfg(feature = "std")]
+    fn write_payload<W: std::io::Write>(data: &T, mut writer: W) -> Result<(), CodecError> {
+        writer.write_all(data.as_ref())?;
+        Ok(())
+    }
+}
+
+impl DecodePayload<Vec<u8>> for RawCodec {
+    fn decode_payload(buf: &[u8]) -> Result<Vec<u8>, CodecError> {
+        Ok(buf.to_vec())
+    }
+}
+
+/// CBOR payloads, the format used by all standard SMP groups.
+#[cfg(feature = "payload-cbor")]
+pub struct CborCodec;
+
+#[cfg(feature = "payload-cbor")]
+impl PayloadCodec for CborCodec {
+    const NAME: &'static str = "cbor";
+}
+
+#[cfg(feature = "payload-cbor")]
+impl<T: serde::Serialize + ?Sized> EncodePayload<T> for CborCodec {
+    fn encode_payload(data: &T, buf: &mut Vec<u8>) -> Result<(), CodecError> {
+        ciborium::ser::into_writer(data, buf)?;
+        Ok(())
+    }
+
+    fn encode_payload_into(data: &T, buf: &mut [u8]) -> Result<usize, CodecError> {
+        let len = buf.len();
+        let mut writer = buf;
+        match ciborium::ser::into_writer(data, &mut writer) {
+            Ok(()) => Ok(len - writer.len()),
+            // writing to a slice only fails if it is full
+            Err(ciborium::ser::Error::Io(_)) => Err(BufferTooSmall.into()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn write_payload<W: std::io::Write>(data: &T, writer: W) -> Result<(), CodecError> {
+        ciborium::ser::into_writer(data, writer)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "payload-cbor")]
+impl<T: serde::de::DeserializeOwned> DecodePayload<T> for CborCodec {
+    fn decode_payload(buf: &[u8]) -> Result<T, CodecError> {
+        Ok(ciborium::de::from_reader(buf)?)
+    }
+}
+
+/// JSON payloads. Not understood by devices, but useful for debugging and tests.
+#[cfg(feature = "payload-json")]
+pub struct JsonCodec;
+
+#[cfg(feature = "payload-json")]
+impl PayloadCodec for JsonCodec {
+    const NAME: &'static str = "json";
+}
+
+#[cfg(feature = "payload-json")]
+impl<T: serde::Serialize + ?Sized> EncodePayload<T> for JsonCodec {
+    fn encode_payload(data: &T, buf: &mut Vec<u8>) -> Result<(), CodecError> {
+        buf.extend_from_slice(&serde_json::to_vec(data)?);
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    fn write_payload<W: std::io::Write>(data: &T, writer: W) -> Result<(), CodecError> {
+        serde_json::to_writer(writer, data)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "payload-json")]
+impl<T: serde::de::DeserializeOwned> DecodePayload<T> for JsonCodec {
+    fn decode_payload(buf: &[u8]) -> Result<T, CodecError> {
+        Ok(serde_json::from_slice(buf)?)
+    }
+}
@@ -0,0 +1,364 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::smp::Group;
+use core::fmt;
+
+/// Defines an error code enum with `TryFrom<i32>`, `From<_> for i32` and a human-readable `Display`.
+macro_rules! error_codes {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($variant:ident = $value:literal => $description:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $(#[doc = $description] $variant = $value,)*
+        }
+
+        impl TryFrom<i32> for $name {
+            type Error = i32;
+
+            /// Returns the unknown code as error
+            fn try_from(rc: i32) -> Result<Self, Self::Error> {
+                match rc {
+                    $($value => Ok(Self::$variant),)*
+                    rc => Err(rc),
+                }
+            }
+        }
+
+        impl From<$name> for i32 {
+            fn from(code: $name) -> Self {
+                code as i32
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self {
+                    $(Self::$variant => f.write_str($description),)*
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// General management error codes (`MGMT_ERR_*`), reported in the `rc` field.
+    pub enum ReturnCode {
+        Ok = 0 => "no error",
+        Unknown = 1 => "unknown error",
+        OutOfMemory = 2 => "insufficient memory",
+        InvalidValue = 3 => "invalid value in request",
+        Timeout = 4 => "operation timed out",
+        NoEntry = 5 => "no such file or entry",
+        BadState = 6 => "current state disallows command",
+        MessageTooLarge = 7 => "response too large",
+        NotSupported = 8 => "command not supported",
+        Corrupt = 9 => "corrupt data",
+        Busy = 10 => "device busy",
+        AccessDenied = 11 => "access denied",
+        UnsupportedTooOld = 12 => "protocol version too old",
+        UnsupportedTooNew = 13 => "protocol version too new",
+        UserDefined = 256 => "user defined error",
+    }
+}
+
+error_codes! {
+    /// OS management group error codes (`OS_MGMT_ERR_*`)
+    pub enum OsMgmtError {
+        Ok = 0 => "no error",
+        Unknown = 1 => "unknown error",
+        InvalidFormat = 2 => "invalid format",
+        QueryYieldsNoAnswer = 3 => "query not recognized",
+        RtcNotSet = 4 => "RTC not set",
+        RtcCommandFailed = 5 => "RTC command failed",
+        QueryResponseValueNotValid = 6 => "query response value not valid",
+    }
+}
+
+error_codes! {
+    /// Image management group error codes (`IMG_MGMT_ERR_*`)
+    pub enum ImageMgmtError {
+        Ok = 0 => "no error",
+        Unknown = 1 => "unknown error",
+        FlashConfigQueryFail = 2 => "failed to query flash area configuration",
+        NoImage = 3 => "no image in slot",
+        NoTlvs = 4 => "image has no TLVs",
+        InvalidTlv = 5 => "invalid TLV",
+        TlvMultipleHashesFound = 6 => "multiple hashes found in image",
+        TlvInvalidSize = 7 => "invalid TLV size",
+        HashNotFound = 8 => "image hash not found",
+        NoFreeSlot = 9 => "no free image slot",
+        FlashOpenFailed = 10 => "failed to open flash area",
+        FlashReadFailed = 11 => "failed to read flash",
+        FlashWriteFailed = 12 => "failed to write flash",
+        FlashEraseFailed = 13 => "failed to erase flash",
+        InvalidSlot = 14 => "invalid image slot",
+        NoFreeMemory = 15 => "insufficient memory",
+        FlashContextAlreadySet = 16 => "flash context already set",
+        FlashContextNotSet = 17 => "flash context not set",
+        FlashAreaDeviceNull = 18 => "flash area device is null",
+        InvalidPageOffset = 19 => "invalid page offset",
+        InvalidOffset = 20 => "invalid offset",
+        InvalidLength = 21 => "invalid length",
+        InvalidImageHeader = 22 => "invalid image header",
+        InvalidImageHeaderMagic = 23 => "invalid image header magic",
+        InvalidHash = 24 => "invalid hash",
+        InvalidFlashAddress = 25 => "invalid flash address",
+        VersionGetFailed = 26 => "failed to get image version",
+        CurrentVersionIsNewer = 27 => "current image version is newer",
+        ImageAlreadyPending = 28 => "image already pending",
+        InvalidImageVectorTable = 29 => "invalid image vector table",
+        InvalidImageTooLarge = 30 => "image too large for slot",
+        InvalidImageDataOverrun = 31 => "image data overrun",
+        ImageConfirmationDenied = 32 => "image confirmation denied",
+        ImageSettingTestToActiveDenied = 33 => "setting active image to test denied",
+        ActiveSlotNotKnown = 34 => "active slot not known",
+    }
+}
+
+error_codes! {
+    /// File system management group error codes (`FS_MGMT_ERR_*`)
+    pub enum FsMgmtError {
+        Ok = 0 => "no error",
+        Unknown = 1 => "unknown error",
+        FileInvalidName = 2 => "invalid file name",
+        FileNotFound = 3 => "file not found",
+        FileIsDirectory = 4 => "file is a directory",
+        FileOpenFailed = 5 => "failed to open file",
+        FileSeekFailed = 6 => "failed to seek in file",
+        FileReadFailed = 7 => "failed to read file",
+        FileTruncateFailed = 8 => "failed to truncate file",
+        FileDeleteFailed = 9 => "failed to delete file",
+        FileWriteFailed = 10 => "failed to write file",
+        FileOffsetNotValid = 11 => "invalid file offset",
+        FileOffsetLargerThanFile = 12 => "offset larger than file",
+        ChecksumHashNotFound = 13 => "checksum or hash type not found",
+        MountPointNotFound = 14 => "mount point not found",
+        ReadOnlyFilesystem = 15 => "read-only file system",
+        FileEmpty = 16 => "file is empty",
+    }
+}
+
+error_codes! {
+    /// Statistics management group error codes (`STAT_MGMT_ERR_*`)
+    pub enum StatsMgmtError {
+        Ok = 0 => "no error",
+        Unknown = 1 => "unknown error",
+        InvalidGroup = 2 => "statistics group not found",
+        InvalidStatName = 3 => "invalid statistic name",
+        InvalidStatSize = 4 => "invalid statistic size",
+        WalkAborted = 5 => "walk through statistics aborted",
+    }
+}
+
+error_codes! {
+    /// Settings management group error codes (`SETTINGS_MGMT_ERR_*`)
+    pub enum SettingsMgmtError {
+        Ok = 0 => "no error",
+        Unknown = 1 => "unknown error",
+        KeyTooLong = 2 => "key too long",
+        KeyNotFound = 3 => "key not found",
+        ReadNotSupported = 4 => "read not supported",
+        RootKeyNotFound = 5 => "root key not found",
+        WriteNotSupported = 6 => "write not supported",
+        DeleteNotSupported = 7 => "delete not supported",
+    }
+}
+
+error_codes! {
+    /// Shell management group error codes (`SHELL_MGMT_ERR_*`)
+    pub enum ShellMgmtError {
+        Ok = 0 => "no error",
+        Unknown = 1 => "unknown error",
+        CommandTooLong = 2 => "command too long",
+        EmptyCommand = 3 => "empty command",
+    }
+}
+
+error_codes! {
+    /// Enumeration management group error codes (`ENUM_MGMT_ERR_*`)
+    pub enum EnumMgmtError {
+        Ok = 0 => "no error",
+        Unknown = 1 => "unknown error",
+        TooManyGroupEntries = 2 => "too many group entries",
+        InsufficientHeapForEntries = 3 => "insufficient heap for entries",
+        IndexTooLarge = 4 => "index too large",
+    }
+}
+
+/// A group-specific error code, as reported in SMP v2 `err: {group, rc}` responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GroupError {
+    Os(OsMgmtError),
+    Image(ImageMgmtError),
+    Stats(StatsMgmtError),
+    Fs(FsMgmtError),
+    Settings(SettingsMgmtError),
+    Shell(ShellMgmtError),
+    Enumeration(EnumMgmtError),
+}
+
+impl GroupError {
+    /// Look up the error code of a group.
+    /// Returns `None` for unknown codes and for groups without defined error codes.
+    pub fn new(group: Group, rc: i32) -> Option<Self> {
+        match group {
+            Group::Default => OsMgmtError::try_from(rc).ok().map(Self::Os),
+            Group::ApplicationManagement => ImageMgmtError::try_from(rc).ok().map(Self::Image),
+            Group::Statistics => StatsMgmtError::try_from(rc).ok().map(Self::Stats),
+            Group::FileManagement => FsMgmtError::try_from(rc).ok().map(Self::Fs),
+            Group::Settings => SettingsMgmtError::try_from(rc).ok().map(Self::Settings),
+            Group::ShellManagement => ShellMgmtError::try_from(rc).ok().map(Self::Shell),
+            Group::Enumeration => EnumMgmtError::try_from(rc).ok().map(Self::Enumeration),
+            _ => None,
+        }
+    }
+
+    pub fn group(&self) -> Group {
+        match self {
+            GroupError::Os(_) => Group::Default,
+            GroupError::Image(_) => Group::ApplicationManagement,
+            GroupError::Stats(_) => Group::Statistics,
+            GroupError::Fs(_) => Group::FileManagement,
+            GroupError::Settings(_) => Group::Settings,
+            GroupError::Shell(_) => Group::ShellManagement,
+            GroupError::Enumeration(_) => Group::Enumeration,
+        }
+    }
+
+    pub fn rc(&self) -> i32 {
+        match *self {
+            GroupError::Os(e) => e.into(),
+            GroupError::Image(e) => e.into(),
+            GroupError::Stats(e) => e.into(),
+            GroupError::Fs(e) => e.into(),
+            GroupError::Settings(e) => e.into(),
+            GroupError::Shell(e) => e.into(),
+            GroupError::Enumeration(e) => e.into(),
+        }
+    }
+
+    /// The closest management error code, used to report the error to SMP v1 clients
+    pub fn return_code(&self) -> ReturnCode {
+        match *self {
+            GroupError::Os(e) => match e {
+                OsMgmtError::Ok => ReturnCode::Ok,
+                OsMgmtError::InvalidFormat => ReturnCode::InvalidValue,
+                OsMgmtError::QueryYieldsNoAnswer => ReturnCode::NoEntry,
+                OsMgmtError::RtcNotSet => ReturnCode::BadState,
+                _ => ReturnCode::Unknown,
+            },
+            GroupError::Image(e) => match e {
+                ImageMgmtError::Ok => ReturnCode::Ok,
+                ImageMgmtError::NoImage | ImageMgmtError::HashNotFound => ReturnCode::NoEntry,
+                ImageMgmtError::NoFreeMemory => ReturnCode::OutOfMemory,
+                ImageMgmtError::InvalidSlot
+                | ImageMgmtError::InvalidPageOffset
+                | ImageMgmtError::InvalidOffset
+                | ImageMgmtError::InvalidLength
+                | ImageMgmtError::InvalidImageHeader
+                | ImageMgmtError::InvalidImageHeaderMagic
+                | ImageMgmtError::InvalidHash
+                | ImageMgmtError::InvalidFlashAddress
+                | ImageMgmtError::InvalidTlv
+                | ImageMgmtError::NoTlvs
+                | ImageMgmtError::TlvMultipleHashesFound
+                | ImageMgmtError::TlvInvalidSize
+                | ImageMgmtError::InvalidImageVectorTable
+                | ImageMgmtError::InvalidImageTooLarge
+                | ImageMgmtError::InvalidImageDataOverrun => ReturnCode::InvalidValue,
+                ImageMgmtError::NoFreeSlot
+                | ImageMgmtError::ImageAlreadyPending
+                | ImageMgmtError::CurrentVersionIsNewer
+                | ImageMgmtError::ImageConfirmationDenied
+                | ImageMgmtError::ImageSettingTestToActiveDenied => ReturnCode::BadState,
+                _ => ReturnCode::Unknown,
+            },
+            GroupError::Stats(e) => match e {
+                StatsMgmtError::Ok => ReturnCode::Ok,
+                StatsMgmtError::InvalidGroup | StatsMgmtError::InvalidStatName => {
+                    ReturnCode::NoEntry
+                }
+                StatsMgmtError::InvalidStatSize => ReturnCode::InvalidValue,
+                _ => ReturnCode::Unknown,
+            },
+            GroupError::Fs(e) => match e {
+                FsMgmtError::Ok => ReturnCode::Ok,
+                FsMgmtError::FileNotFound | FsMgmtError::MountPointNotFound => ReturnCode::NoEntry,
+                FsMgmtError::FileInvalidName
+                | FsMgmtError::FileIsDirectory
+                | FsMgmtError::FileOffsetNotValid
+                | FsMgmtError::FileOffsetLargerThanFile => ReturnCode::InvalidValue,
+                FsMgmtError::ChecksumHashNotFound => ReturnCode::NotSupported,
+                FsMgmtError::ReadOnlyFilesystem => ReturnCode::AccessDenied,
+                _ => ReturnCode::Unknown,
+            },
+            GroupError::Settings(e) => match e {
+                SettingsMgmtError::Ok => ReturnCode::Ok,
+                SettingsMgmtError::KeyTooLong => ReturnCode::InvalidValue,
+                SettingsMgmtError::KeyNotFound | SettingsMgmtError::RootKeyNotFound => {
+                    ReturnCode::NoEntry
+                }
+                SettingsMgmtError::ReadNotSupported
+                | SettingsMgmtError::WriteNotSupported
+                | SettingsMgmtError::DeleteNotSupported => ReturnCode::NotSupported,
+                _ => ReturnCode::Unknown,
+            },
+            GroupError::Shell(e) => match e {
+                ShellMgmtError::Ok => ReturnCode::Ok,
+                ShellMgmtError::CommandTooLong | ShellMgmtError::EmptyCommand => {
+                    ReturnCode::InvalidValue
+                }
+                _ => ReturnCode::Unknown,
+            },
+            GroupError::Enumeration(e) => match e {
+                EnumMgmtError::Ok => ReturnCode::Ok,
+                EnumMgmtError::InsufficientHeapForEntries => ReturnCode::OutOfMemory,
+                EnumMgmtError::TooManyGroupEntries | EnumMgmtError::IndexTooLarge => {
+                    ReturnCode::InvalidValue
+                }
+                _ => ReturnCode::Unknown,
+            },
+        }
+    }
+}
+
+macro_rules! group_error_from {
+    ($($variant:ident($error:ty),)*) => {
+        $(
+            impl From<$error> for GroupError {
+                fn from(err: $error) -> Self {
+                    GroupError::$variant(err)
+                }
+            }
+        )*
+    };
+}
+
+group_error_from! {
+    Os(OsMgmtError),
+    Image(ImageMgmtError),
+    Stats(StatsMgmtError),
+    Fs(FsMgmtError),
+    Settings(SettingsMgmtError),
+    Shell(ShellMgmtError),
+    Enumeration(EnumMgmtError),
+}
+
+impl fmt::Display for GroupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroupError::Os(e) => e.fmt(f),
+            GroupError::Image(e) => e.fmt(f),
+            GroupError::Stats(e) => e.fmt(f),
+            GroupError::Fs(e) => e.fmt(f),
+            GroupError::Settings(e) => e.fmt(f),
+            GroupError::Shell(e) => e.fmt(f),
+            GroupError::Enumeration(e) => e.fmt(f),
+        }
+    }
+}
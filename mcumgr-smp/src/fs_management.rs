@@ -0,0 +1,161 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::response::SmpResponse;
+use crate::{Group, OpCode, SmpFrame, SmpRequest};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+pub enum FileManagementCommand {
+    File,
+    Status,
+    Hash,
+    SupportedHashes,
+    Close,
+    Unknown(u8),
+}
+
+impl FileManagementCommand {
+    pub const fn id(&self) -> u8 {
+        match self {
+            FileManagementCommand::File => 0,
+            FileManagementCommand::Status => 1,
+            FileManagementCommand::Hash => 2,
+            FileManagementCommand::SupportedHashes => 3,
+            FileManagementCommand::Close => 4,
+            FileManagementCommand::Unknown(n) => *n,
+        }
+    }
+}
+
+impl From<FileManagementCommand> for u8 {
+    fn from(cmd: FileManagementCommand) -> Self {
+        cmd.id()
+    }
+}
+
+impl From<u8> for FileManagementCommand {
+    fn from(id: u8) -> Self {
+        match id {
+            0 => FileManagementCommand::File,
+            1 => FileManagementCommand::Status,
+            2 => FileManagementCommand::Hash,
+            3 => FileManagementCommand::SupportedHashes,
+            4 => FileManagementCommand::Close,
+            n => FileManagementCommand::Unknown(n),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FileDownloadRequest {
+    pub off: u64,
+    pub name: String,
+}
+
+impl SmpRequest for FileDownloadRequest {
+    const OPERATION: OpCode = OpCode::ReadRequest;
+    const GROUP: Group = Group::FileManagement;
+    const COMMAND: u8 = FileManagementCommand::File.id();
+    type Response = FileDownloadResponse;
+}
+
+pub fn file_download(sequence: u8, name: String, off: u64) -> SmpFrame<FileDownloadRequest> {
+    FileDownloadRequest { off, name }.into_frame(sequence)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FileDownloadResponse {
+    pub off: u64,
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+    /// total file length, only sent in the response to the first chunk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub len: Option<u64>,
+}
+
+pub type FileDownloadResult = SmpResponse<FileDownloadResponse>;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FileUploadRequest {
+    pub off: u64,
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+    pub name: String,
+    /// total file length, required with the first chunk
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub len: Option<u64>,
+}
+
+impl SmpRequest for FileUploadRequest {
+    const OPERATION: OpCode = OpCode::WriteRequest;
+    const GROUP: Group = Group::FileManagement;
+    const COMMAND: u8 = FileManagementCommand::File.id();
+    type Response = FileUploadResponse;
+}
+
+pub fn file_upload(
+    sequence: u8,
+    name: String,
+    off: u64,
+    data: Vec<u8>,
+    len: Option<u64>,
+) -> SmpFrame<FileUploadRequest> {
+    FileUploadRequest {
+        off,
+        data,
+        name,
+        len,
+    }
+    .into_frame(sequence)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FileUploadResponse {
+    pub off: u64,
+}
+
+pub type FileUploadResult = SmpResponse<FileUploadResponse>;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FileStatusRequest {
+    pub name: String,
+}
+
+impl SmpRequest for FileStatusRequest {
+    const OPERATION: OpCode = OpCode::ReadRequest;
+    const GROUP: Group = Group::FileManagement;
+    const COMMAND: u8 = FileManagementCommand::Status.id();
+    type Response = FileStatusResponse;
+}
+
+pub fn file_status(sequence: u8, name: String) -> SmpFrame<FileStatusRequest> {
+    FileStatusRequest { name }.into_frame(sequence)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FileStatusResponse {
+    pub len: u64,
+}
+
+pub type FileStatusResult = SmpResponse<FileStatusResponse>;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FileCloseRequest {}
+
+impl SmpRequest for FileCloseRequest {
+    const OPERATION: OpCode = OpCode::WriteRequest;
+    const GROUP: Group = Group::FileManagement;
+    const COMMAND: u8 = FileManagementCommand::Close.id();
+    type Response = FileCloseResponse;
+}
+
+pub fn file_close(sequence: u8) -> SmpFrame<FileCloseRequest> {
+    FileCloseRequest {}.into_frame(sequence)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FileCloseResponse {}
+
+pub type FileCloseResult = SmpResponse<FileCloseResponse>;
@@ -15,16 +15,48 @@
 //! #### Bring your own transport
 //! [SmpFrame] is implemented in such a way that it uses raw bytes (i.e. [Vec]) to encode or decode
 //! messages. You can handle this conversion yourself and send these bytes over any channel.
+//!
+//! #### Payload encoding
+//! Payloads are encoded with a [codec::PayloadCodec]. CBOR is used by all standard groups,
+//! [codec::RawCodec] passes through payloads with a custom encoding.
+//!
+//! # `no_std`
+//! Without the default `std` feature the crate only depends on `alloc`. Frames, payloads,
+//! the response envelope and (with the `console-framing` feature) the console framing
+//! encoder/decoder remain available; the transports require `std`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 /// Implementation of a general [SmpFrame] that can have any payload.
 pub mod smp;
 
+/// Payload codecs used to encode and decode [SmpFrame] payloads
+pub mod codec;
+
+/// Management (`rc`) and per-group error codes
+pub mod error_codes;
+
+/// Response envelope shared by all commands, telling success and error payloads apart.
+pub mod response;
+
 #[cfg(feature = "payload-cbor")]
 pub mod application_management;
 #[cfg(feature = "payload-cbor")]
+pub mod fs_management;
+#[cfg(feature = "payload-cbor")]
 pub mod os_management;
 #[cfg(feature = "payload-cbor")]
+pub mod settings_management;
+#[cfg(feature = "payload-cbor")]
 pub mod shell_management;
+#[cfg(feature = "payload-cbor")]
+pub mod stats_management;
+
+/// Device side of the protocol: dispatches requests to per-group handlers
+#[cfg(feature = "server")]
+pub mod server;
 
 /// Implementations over Serial, BLE and UDP transports
 pub mod transport;
@@ -1,52 +1,153 @@
 // Author: Sascha Zenglein <zenglein@gessler.de>
 // Copyright (c) 2023 Gessler GmbH.
-use crate::{Group, SmpFrame};
+use crate::response::SmpResponse;
+use crate::{Group, OpCode, SmpFrame, SmpRequest};
 
-use crate::OpCode::{ReadRequest, WriteRequest};
+use alloc::string::String;
 use serde::{Deserialize, Serialize};
 
+pub enum OsManagementCommand {
+    Echo,
+    ConsoleEchoControl,
+    TaskStat,
+    MemoryPoolStat,
+    DateTime,
+    Reset,
+    McumgrParams,
+    Info,
+    BootloaderInfo,
+    Unknown(u8),
+}
+
+impl OsManagementCommand {
+    pub const fn id(&self) -> u8 {
+        match self {
+            OsManagementCommand::Echo => 0,
+            OsManagementCommand::ConsoleEchoControl => 1,
+            OsManagementCommand::TaskStat => 2,
+            OsManagementCommand::MemoryPoolStat => 3,
+            OsManagementCommand::DateTime => 4,
+            OsManagementCommand::Reset => 5,
+            OsManagementCommand::McumgrParams => 6,
+            OsManagementCommand::Info => 7,
+            OsManagementCommand::BootloaderInfo => 8,
+            OsManagementCommand::Unknown(n) => *n,
+        }
+    }
+}
+
+impl From<OsManagementCommand> for u8 {
+    fn from(cmd: OsManagementCommand) -> Self {
+        cmd.id()
+    }
+}
+
+impl From<u8> for OsManagementCommand {
+    fn from(id: u8) -> Self {
+        match id {
+            0 => OsManagementCommand::Echo,
+            1 => OsManagementCommand::ConsoleEchoControl,
+            2 => OsManagementCommand::TaskStat,
+            3 => OsManagementCommand::MemoryPoolStat,
+            4 => OsManagementCommand::DateTime,
+            5 => OsManagementCommand::Reset,
+            6 => OsManagementCommand::McumgrParams,
+            7 => OsManagementCommand::Info,
+            8 => OsManagementCommand::BootloaderInfo,
+            n => OsManagementCommand::Unknown(n),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct EchoRequest {
     pub d: String,
 }
 
+impl SmpRequest for EchoRequest {
+    const OPERATION: OpCode = OpCode::WriteRequest;
+    const GROUP: Group = Group::Default;
+    const COMMAND: u8 = OsManagementCommand::Echo.id();
+    type Response = EchoResponse;
+}
+
 pub fn echo(sequence: u8, msg: String) -> SmpFrame<EchoRequest> {
-    let payload = EchoRequest { d: msg };
-    SmpFrame::new(WriteRequest, sequence, Group::Default, 0, payload)
+    EchoRequest { d: msg }.into_frame(sequence)
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
-pub enum EchoResult {
-    Ok { r: String },
-    Err { rc: i32 },
+pub struct EchoResponse {
+    pub r: String,
 }
 
+pub type EchoResult = SmpResponse<EchoResponse>;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetInfoRequest {
+    #[serde(default)]
     pub format: String,
 }
 
-pub fn get_info(sequence: u8, format: String) -> SmpFrame<GetInfoRequest> {
-    let request = GetInfoRequest { format };
+impl SmpRequest for GetInfoRequest {
+    const OPERATION: OpCode = OpCode::ReadRequest;
+    const GROUP: Group = Group::Default;
+    const COMMAND: u8 = OsManagementCommand::Info.id();
+    type Response = GetInfoResponse;
+}
 
-    SmpFrame::new(ReadRequest, sequence, Group::Default, 7, request)
+pub fn get_info(sequence: u8, format: String) -> SmpFrame<GetInfoRequest> {
+    GetInfoRequest { format }.into_frame(sequence)
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
-pub enum ResetResult {
-    Ok {},
-    Err { rc: i32 },
+pub struct GetInfoResponse {
+    pub output: String,
 }
 
+pub type GetInfoResult = SmpResponse<GetInfoResponse>;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ResetResponse {}
+
+pub type ResetResult = SmpResponse<ResetResponse>;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ResetRequest {
+    #[serde(default)]
     pub force: u8,
 }
 
+impl SmpRequest for ResetRequest {
+    const OPERATION: OpCode = OpCode::WriteRequest;
+    const GROUP: Group = Group::Default;
+    const COMMAND: u8 = OsManagementCommand::Reset.id();
+    type Response = ResetResponse;
+}
+
 pub fn reset(sequence: u8, force: bool) -> SmpFrame<ResetRequest> {
-    let payload = ResetRequest { force: force as u8 };
+    ResetRequest { force: force as u8 }.into_frame(sequence)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct McumgrParamsRequest {}
+
+impl SmpRequest for McumgrParamsRequest {
+    const OPERATION: OpCode = OpCode::ReadRequest;
+    const GROUP: Group = Group::Default;
+    const COMMAND: u8 = OsManagementCommand::McumgrParams.id();
+    type Response = McumgrParamsResponse;
+}
 
-    SmpFrame::new(WriteRequest, sequence, Group::Default, 5, payload)
+pub fn mcumgr_params(sequence: u8) -> SmpFrame<McumgrParamsRequest> {
+    McumgrParamsRequest {}.into_frame(sequence)
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct McumgrParamsResponse {
+    /// size of the SMP buffers of the device
+    pub buf_size: u32,
+    /// number of SMP buffers of the device
+    pub buf_count: u32,
+}
+
+pub type McumgrParamsResult = SmpResponse<McumgrParamsResponse>;
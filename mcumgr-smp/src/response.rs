@@ -0,0 +1,221 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::error_codes::{
+    EnumMgmtError, FsMgmtError, GroupError, ImageMgmtError, OsMgmtError, ReturnCode,
+    SettingsMgmtError, ShellMgmtError, StatsMgmtError,
+};
+use alloc::string::String;
+use core::fmt;
+
+/// Error reported by the device in a response payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResponseError {
+    /// SMP v1 error: `{"rc": n}` with an optional reason string `rsn`
+    Rc { rc: i32, rsn: Option<String> },
+    /// SMP v2 group error: `{"err": {"group": g, "rc": n}}`
+    Group { group: u16, rc: i32 },
+}
+
+impl ResponseError {
+    /// The management error code of an SMP v1 error, if it is a known code
+    pub fn return_code(&self) -> Option<ReturnCode> {
+        match self {
+            ResponseError::Rc { rc, .. } => ReturnCode::try_from(*rc).ok(),
+            ResponseError::Group { .. } => None,
+        }
+    }
+
+    /// The group error code of an SMP v2 error, if it is a known code
+    pub fn group_error(&self) -> Option<GroupError> {
+        match self {
+            ResponseError::Rc { .. } => None,
+            ResponseError::Group { group, rc } => GroupError::new((*group).into(), *rc),
+        }
+    }
+}
+
+impl fmt::Display for ResponseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResponseError::Rc { rc, rsn } => {
+                match self.return_code() {
+                    Some(code) => write!(f, "{}", code)?,
+                    None => write!(f, "rc: {}", rc)?,
+                }
+                if let Some(rsn) = rsn {
+                    write!(f, " ({})", rsn)?;
+                }
+                Ok(())
+            }
+            ResponseError::Group { group, rc } => match self.group_error() {
+                Some(err) => write!(f, "{}", err),
+                None => write!(f, "group {} rc: {}", group, rc),
+            },
+        }
+    }
+}
+
+impl core::error::Error for ResponseError {}
+
+impl From<ReturnCode> for ResponseError {
+    fn from(code: ReturnCode) -> Self {
+        ResponseError::Rc {
+            rc: code.into(),
+            rsn: None,
+        }
+    }
+}
+
+impl From<GroupError> for ResponseError {
+    fn from(err: GroupError) -> Self {
+        ResponseError::Group {
+            group: err.group().into(),
+            rc: err.rc(),
+        }
+    }
+}
+
+macro_rules! response_error_from_group {
+    ($($error:ty),*) => {
+        $(
+            impl From<$error> for ResponseError {
+                fn from(err: $error) -> Self {
+                    GroupError::from(err).into()
+                }
+            }
+        )*
+    };
+}
+
+response_error_from_group!(
+    OsMgmtError,
+    ImageMgmtError,
+    StatsMgmtError,
+    FsMgmtError,
+    SettingsMgmtError,
+    ShellMgmtError,
+    EnumMgmtError
+);
+
+/// The payload of an SMP response: either the command's success payload or an error.
+///
+/// When decoding, the `err` (v2) and non-zero `rc` (v1) fields are checked before the
+/// success payload is decoded, so a payload type that accepts (almost) any map
+/// can't swallow an error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SmpResponse<T> {
+    Ok(T),
+    Err(ResponseError),
+}
+
+impl<T> SmpResponse<T> {
+    pub fn into_result(self) -> Result<T, ResponseError> {
+        match self {
+            SmpResponse::Ok(data) => Ok(data),
+            SmpResponse::Err(err) => Err(err),
+        }
+    }
+
+    pub fn as_result(&self) -> Result<&T, &ResponseError> {
+        match self {
+            SmpResponse::Ok(data) => Ok(data),
+            SmpResponse::Err(err) => Err(err),
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        matches!(self, SmpResponse::Ok(_))
+    }
+}
+
+impl<T> From<SmpResponse<T>> for Result<T, ResponseError> {
+    fn from(response: SmpResponse<T>) -> Self {
+        response.into_result()
+    }
+}
+
+#[cfg(feature = "payload-cbor")]
+mod serde_impl {
+    use super::{ResponseError, SmpResponse};
+    use alloc::string::String;
+    use ciborium::Value;
+    use serde::de::{DeserializeOwned, Error as _};
+    use serde::ser::SerializeMap;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct GroupError {
+        group: u16,
+        rc: i32,
+    }
+
+    impl Serialize for ResponseError {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                ResponseError::Rc { rc, rsn } => {
+                    let mut map = serializer.serialize_map(None)?;
+                    map.serialize_entry("rc", rc)?;
+                    if let Some(rsn) = rsn {
+                        map.serialize_entry("rsn", rsn)?;
+                    }
+                    map.end()
+                }
+                ResponseError::Group { group, rc } => {
+                    let mut map = serializer.serialize_map(Some(1))?;
+                    map.serialize_entry(
+                        "err",
+                        &GroupError {
+                            group: *group,
+                            rc: *rc,
+                        },
+                    )?;
+                    map.end()
+                }
+            }
+        }
+    }
+
+    impl<T: Serialize> Serialize for SmpResponse<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                SmpResponse::Ok(data) => data.serialize(serializer),
+                SmpResponse::Err(err) => err.serialize(serializer),
+            }
+        }
+    }
+
+    impl<'de, T: DeserializeOwned> Deserialize<'de> for SmpResponse<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let value = Value::deserialize(deserializer)?;
+            let field = |name: &str| {
+                value.as_map().and_then(|map| {
+                    map.iter()
+                        .find(|(key, _)| key.as_text() == Some(name))
+                        .map(|(_, value)| value)
+                })
+            };
+
+            if let Some(err) = field("err") {
+                let err: GroupError = err.deserialized().map_err(D::Error::custom)?;
+                return Ok(SmpResponse::Err(ResponseError::Group {
+                    group: err.group,
+                    rc: err.rc,
+                }));
+            }
+
+            // v1 responses may include `rc: 0` on success
+            if let Some(rc) = field("rc") {
+                let rc: i32 = rc.deserialized().map_err(D::Error::custom)?;
+                if rc != 0 {
+                    let rsn = field("rsn").and_then(Value::as_text).map(String::from);
+                    return Ok(SmpResponse::Err(ResponseError::Rc { rc, rsn }));
+                }
+            }
+
+            value
+                .deserialized()
+                .map(SmpResponse::Ok)
+                .map_err(D::Error::custom)
+        }
+    }
+}
@@ -0,0 +1,453 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::application_management::{
+    ApplicationManagementCommand, EraseRequest, EraseResponse, GetImageStatePayload,
+    GetStatePayload, ImageUploadRequest, SetStatePayload, WriteImageChunkPayload,
+};
+use crate::codec::{CborCodec, DecodePayload, EncodePayload, RawCodec};
+use crate::error_codes::{GroupError, ReturnCode};
+use crate::fs_management::{
+    FileCloseRequest, FileCloseResponse, FileDownloadRequest, FileDownloadResponse,
+    FileManagementCommand, FileStatusRequest, FileStatusResponse, FileUploadRequest,
+    FileUploadResponse,
+};
+use crate::os_management::{
+    EchoRequest, EchoResponse, GetInfoRequest, GetInfoResponse, McumgrParamsRequest,
+    McumgrParamsResponse, OsManagementCommand, ResetRequest, ResetResponse,
+};
+use crate::response::ResponseError;
+use crate::settings_management::{
+    CommitSettingsRequest, DeleteSettingRequest, LoadSettingsRequest, ReadSettingRequest,
+    ReadSettingResponse, SaveSettingsRequest, SettingsManagementCommand, SettingsResponse,
+    WriteSettingRequest,
+};
+use crate::shell_management::{ShellCommand, ShellManagementCommand, ShellResponse};
+use crate::smp::{Group, OpCode, SmpError, SmpFrame, SmpHeader, SmpRequest, SmpVersion};
+use crate::stats_management::{
+    StatsListRequest, StatsListResponse, StatsManagementCommand, StatsReadRequest,
+    StatsReadResponse,
+};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Result of a handler method, errors are sent to the client
+pub type HandlerResult<T> = Result<T, ResponseError>;
+
+fn not_supported<T>() -> HandlerResult<T> {
+    Err(ReturnCode::NotSupported.into())
+}
+
+/// OS management group
+pub trait OsHandler {
+    /// Defaults to echoing the string back
+    fn echo(&mut self, request: EchoRequest) -> HandlerResult<EchoResponse> {
+        Ok(EchoResponse { r: request.d })
+    }
+
+    /// Called before the response is sent, the reset should happen after sending it
+    fn reset(&mut self, _request: ResetRequest) -> HandlerResult<ResetResponse> {
+        not_supported()
+    }
+
+    fn info(&mut self, _request: GetInfoRequest) -> HandlerResult<GetInfoResponse> {
+        not_supported()
+    }
+
+    fn mcumgr_params(
+        &mut self,
+        _request: McumgrParamsRequest,
+    ) -> HandlerResult<McumgrParamsResponse> {
+        not_supported()
+    }
+}
+
+/// Image (application) management group
+pub trait ImageHandler {
+    fn state(&mut self, _request: GetStatePayload) -> HandlerResult<GetImageStatePayload> {
+        not_supported()
+    }
+
+    /// Mark an image for test or confirm it, responds with the new image states
+    fn set_state(&mut self, _request: SetStatePayload) -> HandlerResult<GetImageStatePayload> {
+        not_supported()
+    }
+
+    fn upload(&mut self, _request: ImageUploadRequest) -> HandlerResult<WriteImageChunkPayload> {
+        not_supported()
+    }
+
+    fn erase(&mut self, _request: EraseRequest) -> HandlerResult<EraseResponse> {
+        not_supported()
+    }
+}
+
+/// Statistics management group
+pub trait StatsHandler {
+    fn read(&mut self, _request: StatsReadRequest) -> HandlerResult<StatsReadResponse> {
+        not_supported()
+    }
+
+    fn list(&mut self, _request: StatsListRequest) -> HandlerResult<StatsListResponse> {
+        not_supported()
+    }
+}
+
+/// File system management group
+pub trait FsHandler {
+    fn download(&mut self, _request: FileDownloadRequest) -> HandlerResult<FileDownloadResponse> {
+        not_supported()
+    }
+
+    fn upload(&mut self, _request: FileUploadRequest) -> HandlerResult<FileUploadResponse> {
+        not_supported()
+    }
+
+    fn status(&mut self, _request: FileStatusRequest) -> HandlerResult<FileStatusResponse> {
+        not_supported()
+    }
+
+    /// Close files left open by uploads or downloads
+    fn close(&mut self, _request: FileCloseRequest) -> HandlerResult<FileCloseResponse> {
+        not_supported()
+    }
+}
+
+/// Shell management group
+pub trait ShellHandler {
+    fn execute(&mut self, _request: ShellCommand) -> HandlerResult<ShellResponse> {
+        not_supported()
+    }
+}
+
+/// Settings management group
+pub trait SettingsHandler {
+    fn read(&mut self, _request: ReadSettingRequest) -> HandlerResult<ReadSettingResponse> {
+        not_supported()
+    }
+
+    fn write(&mut self, _request: WriteSettingRequest) -> HandlerResult<SettingsResponse> {
+        not_supported()
+    }
+
+    fn delete(&mut self, _request: DeleteSettingRequest) -> HandlerResult<SettingsResponse> {
+        not_supported()
+    }
+
+    fn commit(&mut self, _request: CommitSettingsRequest) -> HandlerResult<SettingsResponse> {
+        not_supported()
+    }
+
+    fn load(&mut self, _request: LoadSettingsRequest) -> HandlerResult<SettingsResponse> {
+        not_supported()
+    }
+
+    fn save(&mut self, _request: SaveSettingsRequest) -> HandlerResult<SettingsResponse> {
+        not_supported()
+    }
+}
+
+/// Handler for a group without a dedicated trait, or to replace a built-in group.
+/// Payloads are passed as raw bytes, so any payload encoding can be used.
+pub trait CustomHandler {
+    /// Handle a request and return the encoded response payload
+    fn handle(&mut self, header: &SmpHeader) -> HandlerResult<Vec<u8>>;
+}
+
+/// Dispatches incoming request frames to the handler registered for their group.
+///
+/// Responses use the SMP version of the request. Group errors are sent as `err: {group, rc}`
+/// to version 2 clients and translated to the closest `rc` for version 1 clients.
+/// Requests for groups or commands without a handler are answered with
+/// [ReturnCode::NotSupported], request payloads that can't be decoded with
+/// [ReturnCode::InvalidValue].
+///
+/// All handler methods have a default implementation that answers with
+/// [ReturnCode::NotSupported], so a handler only implements the commands it supports.
+/// Errors are returned as [ResponseError], which all error code enums convert into:
+///
+/// ```
+/// use mcumgr_smp::error_codes::ShellMgmtError;
+/// use mcumgr_smp::server::{HandlerResult, ShellHandler, SmpServer};
+/// use mcumgr_smp::shell_management::{ShellCommand, ShellResponse};
+///
+/// struct Shell;
+///
+/// impl ShellHandler for Shell {
+///     fn execute(&mut self, request: ShellCommand) -> HandlerResult<ShellResponse> {
+///         if request.argv.is_empty() {
+///             return Err(ShellMgmtError::EmptyCommand.into());
+///         }
+///         Ok(ShellResponse {
+///             o: request.argv.join(" "),
+///             ret: 0,
+///         })
+///     }
+/// }
+///
+/// let mut server = SmpServer::new().with_shell(Shell);
+/// # let request = mcumgr_smp::shell_management::shell_command(0, vec!["hello".into()]);
+/// # let request = request.encode_with_cbor().unwrap();
+/// let response = server.process(&request).unwrap();
+/// ```
+#[derive(Default)]
+pub struct SmpServer {
+    os: Option<Box<dyn OsHandler + Send>>,
+    image: Option<Box<dyn ImageHandler + Send>>,
+    stats: Option<Box<dyn StatsHandler + Send>>,
+    fs: Option<Box<dyn FsHandler + Send>>,
+    shell: Option<Box<dyn ShellHandler + Send>>,
+    settings: Option<Box<dyn SettingsHandler + Send>>,
+    custom: BTreeMap<u16, Box<dyn CustomHandler + Send>>,
+}
+
+impl SmpServer {
+    /// Create a server without handlers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_os(mut self, handler: impl OsHandler + Send + 'static) -> Self {
+        self.os = Some(Box::new(handler));
+        self
+    }
+
+    pub fn with_image(mut self, handler: impl ImageHandler + Send + 'static) -> Self {
+        self.image = Some(Box::new(handler));
+        self
+    }
+
+    pub fn with_stats(mut self, handler: impl StatsHandler + Send + 'static) -> Self {
+        self.stats = Some(Box::new(handler));
+        self
+    }
+
+    pub fn with_fs(mut self, handler: impl FsHandler + Send + 'static) -> Self {
+        self.fs = Some(Box::new(handler));
+        self
+    }
+
+    pub fn with_shell(mut self, handler: impl ShellHandler + Send + 'static) -> Self {
+        self.shell = Some(Box::new(handler));
+        self
+    }
+
+    pub fn with_settings(mut self, handler: impl SettingsHandler + Send + 'static) -> Self {
+        self.settings = Some(Box::new(handler));
+        self
+    }
+
+    /// Register a handler for `group`.
+    /// Takes precedence over the handler traits of the built-in groups.
+    pub fn with_group(
+        mut self,
+        group: Group,
+        handler: impl CustomHandler + Send + 'static,
+    ) -> Self {
+        self.custom.insert(group.into(), Box::new(handler));
+        self
+    }
+
+    /// Handle a request frame and return the encoded response.
+    /// Returns `None` for frames that aren't requests, and an error if the frame can't be decoded.
+    pub fn process(&mut self, request: &[u8]) -> Result<Option<Vec<u8>>, SmpError> {
+        let header = SmpHeader::parse(request)?;
+        let operation = match header.operation() {
+            OpCode::ReadRequest => OpCode::ReadResponse,
+            OpCode::WriteRequest => OpCode::WriteResponse,
+            OpCode::ReadResponse | OpCode::WriteResponse => return Ok(None),
+        };
+
+        let payload = match self.dispatch(&header) {
+            Ok(payload) => payload,
+            Err(err) => encode_error(err, header.version())?,
+        };
+
+        let response = SmpFrame {
+            operation,
+            version: header.version(),
+            flags: 0,
+            group: header.group(),
+            sequence: header.sequence(),
+            command: header.command(),
+            data: payload,
+        };
+        response.encode::<RawCodec>().map(Some)
+    }
+
+    fn dispatch(&mut self, header: &SmpHeader) -> HandlerResult<Vec<u8>> {
+        if let Some(handler) = self.custom.get_mut(&header.group().into()) {
+            return handler.handle(header);
+        }
+
+        match header.group() {
+            Group::Default => match self.os.as_deref_mut() {
+                Some(handler) => dispatch_os(handler, header),
+                None => not_supported(),
+            },
+            Group::ApplicationManagement => match self.image.as_deref_mut() {
+                Some(handler) => dispatch_image(handler, header),
+                None => not_supported(),
+            },
+            Group::Statistics => match self.stats.as_deref_mut() {
+                Some(handler) => dispatch_stats(handler, header),
+                None => not_supported(),
+            },
+            Group::FileManagement => match self.fs.as_deref_mut() {
+                Some(handler) => dispatch_fs(handler, header),
+                None => not_supported(),
+            },
+            Group::ShellManagement => match self.shell.as_deref_mut() {
+                Some(handler) => dispatch_shell(handler, header),
+                None => not_supported(),
+            },
+            Group::Settings => match self.settings.as_deref_mut() {
+                Some(handler) => dispatch_settings(handler, header),
+                None => not_supported(),
+            },
+            _ => not_supported(),
+        }
+    }
+}
+
+/// Decode the request, call the handler and encode its response payload
+fn call<R>(
+    header: &SmpHeader,
+    handler: impl FnOnce(R) -> HandlerResult<R::Response>,
+) -> HandlerResult<Vec<u8>>
+where
+    R: SmpRequest + DeserializeOwned,
+    R::Response: Serialize,
+{
+    // some clients send no payload at all instead of an empty map
+    let payload = match header.payload() {
+        [] => &[0xa0],
+        payload => payload,
+    };
+    let request: R = CborCodec::decode_payload(payload).map_err(|_| ReturnCode::InvalidValue)?;
+    let response = handler(request)?;
+
+    let mut buf = Vec::new();
+    CborCodec::encode_payload(&response, &mut buf).map_err(|_| ReturnCode::Unknown)?;
+    Ok(buf)
+}
+
+fn dispatch_os(handler: &mut dyn OsHandler, header: &SmpHeader) -> HandlerResult<Vec<u8>> {
+    match (
+        OsManagementCommand::from(header.command()),
+        header.operation(),
+    ) {
+        (OsManagementCommand::Echo, OpCode::WriteRequest) => call(header, |r| handler.echo(r)),
+        (OsManagementCommand::Reset, OpCode::WriteRequest) => call(header, |r| handler.reset(r)),
+        (OsManagementCommand::Info, OpCode::ReadRequest) => call(header, |r| handler.info(r)),
+        (OsManagementCommand::McumgrParams, OpCode::ReadRequest) => {
+            call(header, |r| handler.mcumgr_params(r))
+        }
+        _ => not_supported(),
+    }
+}
+
+fn dispatch_image(handler: &mut dyn ImageHandler, header: &SmpHeader) -> HandlerResult<Vec<u8>> {
+    match (
+        ApplicationManagementCommand::from(header.command()),
+        header.operation(),
+    ) {
+        (ApplicationManagementCommand::State, OpCode::ReadRequest) => {
+            call(header, |r| handler.state(r))
+        }
+        (ApplicationManagementCommand::State, OpCode::WriteRequest) => {
+            call(header, |r| handler.set_state(r))
+        }
+        (ApplicationManagementCommand::Upload, OpCode::WriteRequest) => {
+            call(header, |r| handler.upload(r))
+        }
+        (ApplicationManagementCommand::Erase, OpCode::WriteRequest) => {
+            call(header, |r| handler.erase(r))
+        }
+        _ => not_supported(),
+    }
+}
+
+fn dispatch_stats(handler: &mut dyn StatsHandler, header: &SmpHeader) -> HandlerResult<Vec<u8>> {
+    match (
+        StatsManagementCommand::from(header.command()),
+        header.operation(),
+    ) {
+        (StatsManagementCommand::Read, OpCode::ReadRequest) => call(header, |r| handler.read(r)),
+        (StatsManagementCommand::List, OpCode::ReadRequest) => call(header, |r| handler.list(r)),
+        _ => not_supported(),
+    }
+}
+
+fn dispatch_fs(handler: &mut dyn FsHandler, header: &SmpHeader) -> HandlerResult<Vec<u8>> {
+    match (
+        FileManagementCommand::from(header.command()),
+        header.operation(),
+    ) {
+        (FileManagementCommand::File, OpCode::ReadRequest) => call(header, |r| handler.download(r)),
+        (FileManagementCommand::File, OpCode::WriteRequest) => call(header, |r| handler.upload(r)),
+        (FileManagementCommand::Status, OpCode::ReadRequest) => call(header, |r| handler.status(r)),
+        (FileManagementCommand::Close, OpCode::WriteRequest) => call(header, |r| handler.close(r)),
+        _ => not_supported(),
+    }
+}
+
+fn dispatch_shell(handler: &mut dyn ShellHandler, header: &SmpHeader) -> HandlerResult<Vec<u8>> {
+    match (
+        ShellManagementCommand::from(header.command()),
+        header.operation(),
+    ) {
+        (ShellManagementCommand::Execute, OpCode::WriteRequest) => {
+            call(header, |r| handler.execute(r))
+        }
+        _ => not_supported(),
+    }
+}
+
+fn dispatch_settings(
+    handler: &mut dyn SettingsHandler,
+    header: &SmpHeader,
+) -> HandlerResult<Vec<u8>> {
+    match (
+        SettingsManagementCommand::from(header.command()),
+        header.operation(),
+    ) {
+        (SettingsManagementCommand::ReadWrite, OpCode::ReadRequest) => {
+            call(header, |r| handler.read(r))
+        }
+        (SettingsManagementCommand::ReadWrite, OpCode::WriteRequest) => {
+            call(header, |r| handler.write(r))
+        }
+        (SettingsManagementCommand::Delete, OpCode::WriteRequest) => {
+            call(header, |r| handler.delete(r))
+        }
+        (SettingsManagementCommand::Commit, OpCode::WriteRequest) => {
+            call(header, |r| handler.commit(r))
+        }
+        (SettingsManagementCommand::LoadSave, OpCode::ReadRequest) => {
+            call(header, |r| handler.load(r))
+        }
+        (SettingsManagementCommand::LoadSave, OpCode::WriteRequest) => {
+            call(header, |r| handler.save(r))
+        }
+        _ => not_supported(),
+    }
+}
+
+/// Encode an error payload, translating group errors for SMP v1 clients
+fn encode_error(err: ResponseError, version: SmpVersion) -> Result<Vec<u8>, SmpError> {
+    let err = match (version, err) {
+        (SmpVersion::V1, ResponseError::Group { group, rc }) => {
+            let code = GroupError::new(group.into(), rc)
+                .map_or(ReturnCode::Unknown, |err| err.return_code());
+            code.into()
+        }
+        (_, err) => err,
+    };
+
+    let mut buf = Vec::new();
+    CborCodec::encode_payload(&err, &mut buf).map_err(SmpError::PayloadEncodingError)?;
+    Ok(buf)
+}
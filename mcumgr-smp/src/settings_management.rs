@@ -0,0 +1,161 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::response::SmpResponse;
+use crate::{Group, OpCode, SmpFrame, SmpRequest};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+pub enum SettingsManagementCommand {
+    ReadWrite,
+    Delete,
+    Commit,
+    LoadSave,
+    Unknown(u8),
+}
+
+impl SettingsManagementCommand {
+    pub const fn id(&self) -> u8 {
+        match self {
+            SettingsManagementCommand::ReadWrite => 0,
+            SettingsManagementCommand::Delete => 1,
+            SettingsManagementCommand::Commit => 2,
+            SettingsManagementCommand::LoadSave => 3,
+            SettingsManagementCommand::Unknown(n) => *n,
+        }
+    }
+}
+
+impl From<SettingsManagementCommand> for u8 {
+    fn from(cmd: SettingsManagementCommand) -> Self {
+        cmd.id()
+    }
+}
+
+impl From<u8> for SettingsManagementCommand {
+    fn from(id: u8) -> Self {
+        match id {
+            0 => SettingsManagementCommand::ReadWrite,
+            1 => SettingsManagementCommand::Delete,
+            2 => SettingsManagementCommand::Commit,
+            3 => SettingsManagementCommand::LoadSave,
+            n => SettingsManagementCommand::Unknown(n),
+        }
+    }
+}
+
+/// Response to the settings commands that don't return data
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SettingsResponse {}
+
+pub type SettingsResult = SmpResponse<SettingsResponse>;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReadSettingRequest {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u32>,
+}
+
+impl SmpRequest for ReadSettingRequest {
+    const OPERATION: OpCode = OpCode::ReadRequest;
+    const GROUP: Group = Group::Settings;
+    const COMMAND: u8 = SettingsManagementCommand::ReadWrite.id();
+    type Response = ReadSettingResponse;
+}
+
+pub fn read_setting(sequence: u8, name: String) -> SmpFrame<ReadSettingRequest> {
+    ReadSettingRequest {
+        name,
+        max_size: None,
+    }
+    .into_frame(sequence)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReadSettingResponse {
+    #[serde(with = "serde_bytes")]
+    pub val: Vec<u8>,
+    /// set if the value was truncated to the device's maximum size
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u32>,
+}
+
+pub type ReadSettingResult = SmpResponse<ReadSettingResponse>;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WriteSettingRequest {
+    pub name: String,
+    #[serde(with = "serde_bytes")]
+    pub val: Vec<u8>,
+}
+
+impl SmpRequest for WriteSettingRequest {
+    const OPERATION: OpCode = OpCode::WriteRequest;
+    const GROUP: Group = Group::Settings;
+    const COMMAND: u8 = SettingsManagementCommand::ReadWrite.id();
+    type Response = SettingsResponse;
+}
+
+pub fn write_setting(sequence: u8, name: String, val: Vec<u8>) -> SmpFrame<WriteSettingRequest> {
+    WriteSettingRequest { name, val }.into_frame(sequence)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DeleteSettingRequest {
+    pub name: String,
+}
+
+impl SmpRequest for DeleteSettingRequest {
+    const OPERATION: OpCode = OpCode::WriteRequest;
+    const GROUP: Group = Group::Settings;
+    const COMMAND: u8 = SettingsManagementCommand::Delete.id();
+    type Response = SettingsResponse;
+}
+
+pub fn delete_setting(sequence: u8, name: String) -> SmpFrame<DeleteSettingRequest> {
+    DeleteSettingRequest { name }.into_frame(sequence)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommitSettingsRequest {}
+
+impl SmpRequest for CommitSettingsRequest {
+    const OPERATION: OpCode = OpCode::WriteRequest;
+    const GROUP: Group = Group::Settings;
+    const COMMAND: u8 = SettingsManagementCommand::Commit.id();
+    type Response = SettingsResponse;
+}
+
+pub fn commit_settings(sequence: u8) -> SmpFrame<CommitSettingsRequest> {
+    CommitSettingsRequest {}.into_frame(sequence)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LoadSettingsRequest {}
+
+impl SmpRequest for LoadSettingsRequest {
+    const OPERATION: OpCode = OpCode::ReadRequest;
+    const GROUP: Group = Group::Settings;
+    const COMMAND: u8 = SettingsManagementCommand::LoadSave.id();
+    type Response = SettingsResponse;
+}
+
+pub fn load_settings(sequence: u8) -> SmpFrame<LoadSettingsRequest> {
+    LoadSettingsRequest {}.into_frame(sequence)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SaveSettingsRequest {}
+
+impl SmpRequest for SaveSettingsRequest {
+    const OPERATION: OpCode = OpCode::WriteRequest;
+    const GROUP: Group = Group::Settings;
+    const COMMAND: u8 = SettingsManagementCommand::LoadSave.id();
+    type Response = SettingsResponse;
+}
+
+pub fn save_settings(sequence: u8) -> SmpFrame<SaveSettingsRequest> {
+    SaveSettingsRequest {}.into_frame(sequence)
+}
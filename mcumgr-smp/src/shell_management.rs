@@ -1,10 +1,41 @@
 // Author: Sascha Zenglein <zenglein@gessler.de>
 // Copyright (c) 2023 Gessler GmbH.
-use crate::{Group, SmpFrame};
+use crate::response::SmpResponse;
+use crate::{Group, OpCode, SmpFrame, SmpRequest};
 
-use crate::OpCode::WriteRequest;
+use alloc::string::String;
+use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 
+pub enum ShellManagementCommand {
+    Execute,
+    Unknown(u8),
+}
+
+impl ShellManagementCommand {
+    pub const fn id(&self) -> u8 {
+        match self {
+            ShellManagementCommand::Execute => 0,
+            ShellManagementCommand::Unknown(n) => *n,
+        }
+    }
+}
+
+impl From<ShellManagementCommand> for u8 {
+    fn from(cmd: ShellManagementCommand) -> Self {
+        cmd.id()
+    }
+}
+
+impl From<u8> for ShellManagementCommand {
+    fn from(id: u8) -> Self {
+        match id {
+            0 => ShellManagementCommand::Execute,
+            n => ShellManagementCommand::Unknown(n),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ShellCommand {
     /// argv containing cmd + arg, arg, ...
@@ -12,23 +43,22 @@ pub struct ShellCommand {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
-pub enum ShellResult {
-    Ok { o: String, ret: i32 },
-    Err { rc: i32 },
+pub struct ShellResponse {
+    /// output of the command
+    pub o: String,
+    /// return code of the command
+    pub ret: i32,
 }
 
-impl ShellResult {
-    pub fn into_result(self) -> Result<(String, i32), i32> {
-        match self {
-            ShellResult::Ok { o, ret } => Ok((o, ret)),
-            ShellResult::Err { rc } => Err(rc),
-        }
-    }
+pub type ShellResult = SmpResponse<ShellResponse>;
+
+impl SmpRequest for ShellCommand {
+    const OPERATION: OpCode = OpCode::WriteRequest;
+    const GROUP: Group = Group::ShellManagement;
+    const COMMAND: u8 = ShellManagementCommand::Execute.id();
+    type Response = ShellResponse;
 }
 
 pub fn shell_command(sequence: u8, command_args: Vec<String>) -> SmpFrame<ShellCommand> {
-    let payload = ShellCommand { argv: command_args };
-
-    SmpFrame::new(WriteRequest, sequence, Group::ShellManagement, 0, payload)
+    ShellCommand { argv: command_args }.into_frame(sequence)
 }
@@ -1,19 +1,37 @@
 // Author: Sascha Zenglein <zenglein@gessler.de>
 // Copyright (c) 2023 Gessler GmbH.
 
+#[cfg(feature = "payload-cbor")]
+use crate::codec::CborCodec;
+use crate::codec::{BufferTooSmall, CodecError, DecodePayload, EncodePayload};
+use alloc::vec::Vec;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum SmpError {
     #[error("payload decoding error: {0}")]
-    PayloadDecodingError(#[from] Box<dyn std::error::Error>),
+    PayloadDecodingError(CodecError),
+    #[error("payload encoding error: {0}")]
+    PayloadEncodingError(CodecError),
+    #[error("payload too large: {0} bytes")]
+    PayloadTooLarge(usize),
     #[error("smp frame decoding error")]
     InvalidFrame,
+    #[error("unknown opcode: {0}")]
+    UnknownOpCode(u8),
+    #[error("unsupported smp version: {0}")]
+    UnsupportedVersion(u8),
+    /// No response to a request with this sequence number is awaited
     #[error("unexpected sequence number")]
     UnexpectedSeq,
+    #[error("output buffer too small")]
+    BufferTooSmall,
+    #[cfg(feature = "std")]
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OpCode {
     ReadRequest = 0,
     ReadResponse = 1,
@@ -21,14 +39,16 @@ pub enum OpCode {
     WriteResponse = 3,
 }
 
-impl From<u8> for OpCode {
-    fn from(num: u8) -> Self {
+impl TryFrom<u8> for OpCode {
+    type Error = SmpError;
+
+    fn try_from(num: u8) -> Result<Self, Self::Error> {
         match num {
-            0 => OpCode::ReadRequest,
-            1 => OpCode::ReadResponse,
-            2 => OpCode::WriteRequest,
-            3 => OpCode::WriteResponse,
-            _ => panic!("unknown opcode"),
+            0 => Ok(OpCode::ReadRequest),
+            1 => Ok(OpCode::ReadResponse),
+            2 => Ok(OpCode::WriteRequest),
+            3 => Ok(OpCode::WriteResponse),
+            num => Err(SmpError::UnknownOpCode(num)),
         }
     }
 }
@@ -44,13 +64,15 @@ impl From<OpCode> for u8 {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Group {
     Default,
     ApplicationManagement,
     Statistics,
+    Settings,
     FileManagement,
     ShellManagement,
+    Enumeration,
     ZephyrCommand,
     Custom(u16),
 }
@@ -61,8 +83,10 @@ impl From<u16> for Group {
             0 => Self::Default,
             1 => Self::ApplicationManagement,
             2 => Self::Statistics,
+            3 => Self::Settings,
             8 => Self::FileManagement,
             9 => Self::ShellManagement,
+            10 => Self::Enumeration,
             63 => Self::ZephyrCommand,
             num => Self::Custom(num),
         }
@@ -75,20 +99,149 @@ impl From<Group> for u16 {
             Group::Default => 0,
             Group::ApplicationManagement => 1,
             Group::Statistics => 2,
+            Group::Settings => 3,
             Group::FileManagement => 8,
             Group::ShellManagement => 9,
+            Group::Enumeration => 10,
             Group::ZephyrCommand => 63,
             Group::Custom(num) => num,
         }
     }
 }
 
-pub enum ReturnCode {
-    Ok = 0,
-    Unknown = 1,
-    OutOfMemory = 2,
-    // ...
-    UserDefined = 256,
+/// SMP protocol version, carried in bits 3-4 of the first header byte.
+///
+/// Version 2 responses report group-specific errors as `err: {group, rc}`,
+/// while version 1 responses only carry a single `rc` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum SmpVersion {
+    /// Legacy SMP, as used by mynewt and Zephyr before 3.3
+    V1 = 0,
+    /// SMP version 2
+    #[default]
+    V2 = 1,
+}
+
+impl TryFrom<u8> for SmpVersion {
+    type Error = SmpError;
+
+    fn try_from(num: u8) -> Result<Self, Self::Error> {
+        match num {
+            0 => Ok(SmpVersion::V1),
+            1 => Ok(SmpVersion::V2),
+            num => Err(SmpError::UnsupportedVersion(num)),
+        }
+    }
+}
+
+impl From<SmpVersion> for u8 {
+    fn from(version: SmpVersion) -> Self {
+        match version {
+            SmpVersion::V1 => 0,
+            SmpVersion::V2 => 1,
+        }
+    }
+}
+
+pub use crate::error_codes::ReturnCode;
+
+/// A request payload that knows its operation, group, command and response type.
+///
+/// Implemented for all request payloads in the group modules. This ties each request to the
+/// payload of a successful response, so transports can decode the response without
+/// the caller having to name its type.
+pub trait SmpRequest {
+    const OPERATION: OpCode;
+    const GROUP: Group;
+    const COMMAND: u8;
+
+    /// Payload of a successful response
+    type Response;
+
+    /// Build a frame for this request
+    fn into_frame(self, sequence: u8) -> SmpFrame<Self>
+    where
+        Self: Sized,
+    {
+        SmpFrame::new(Self::OPERATION, sequence, Self::GROUP, Self::COMMAND, self)
+    }
+}
+
+/// Zero-copy view of the 8 byte header of an encoded SMP frame.
+///
+/// This allows routing or filtering frames by group and command without
+/// decoding the payload.
+#[derive(Debug, Clone, Copy)]
+pub struct SmpHeader<'a> {
+    operation: OpCode,
+    version: SmpVersion,
+    /// header and payload, without any trailing bytes
+    frame: &'a [u8],
+}
+
+impl<'a> SmpHeader<'a> {
+    /// Length of the encoded header
+    pub const LEN: usize = 8;
+
+    /// Parse the header at the start of `buf`.  
+    /// Fails if the opcode or version is unknown, or if `buf` is shorter than
+    /// the payload length given in the header. Trailing bytes are ignored.
+    pub fn parse(buf: &'a [u8]) -> Result<Self, SmpError> {
+        if buf.len() < Self::LEN {
+            return Err(SmpError::InvalidFrame);
+        }
+
+        let operation = OpCode::try_from(buf[0] & 0x07)?;
+        let version = SmpVersion::try_from((buf[0] >> 3) & 0x03)?;
+        let frame_len = Self::LEN + u16::from_be_bytes([buf[2], buf[3]]) as usize;
+
+        let frame = buf.get(..frame_len).ok_or(SmpError::InvalidFrame)?;
+
+        Ok(Self {
+            operation,
+            version,
+            frame,
+        })
+    }
+
+    pub fn operation(&self) -> OpCode {
+        self.operation
+    }
+
+    pub fn version(&self) -> SmpVersion {
+        self.version
+    }
+
+    pub fn flags(&self) -> u8 {
+        self.frame[1]
+    }
+
+    /// Length of the payload
+    pub fn length(&self) -> u16 {
+        u16::from_be_bytes([self.frame[2], self.frame[3]])
+    }
+
+    pub fn group(&self) -> Group {
+        Group::from(u16::from_be_bytes([self.frame[4], self.frame[5]]))
+    }
+
+    pub fn sequence(&self) -> u8 {
+        self.frame[6]
+    }
+
+    pub fn command(&self) -> u8 {
+        self.frame[7]
+    }
+
+    /// The still encoded payload
+    pub fn payload(&self) -> &'a [u8] {
+        &self.frame[Self::LEN..]
+    }
+
+    /// Header and payload bytes of the frame
+    pub fn frame(&self) -> &'a [u8] {
+        self.frame
+    }
 }
 
 /// Definitition of a single SMP message.  
@@ -96,6 +249,7 @@ pub enum ReturnCode {
 #[derive(Debug, Clone)]
 pub struct SmpFrame<T> {
     pub operation: OpCode,
+    pub version: SmpVersion,
     pub flags: u8,
     pub group: Group,
     pub sequence: u8,
@@ -104,10 +258,11 @@ pub struct SmpFrame<T> {
 }
 
 impl<T> SmpFrame<T> {
-    ///  Create new with default flags
+    ///  Create new with default flags and protocol version
     pub fn new(operation: OpCode, sequence: u8, group: Group, command: u8, payload: T) -> Self {
         Self {
             operation,
+            version: SmpVersion::default(),
             flags: 0,
             group,
             sequence,
@@ -118,55 +273,109 @@ impl<T> SmpFrame<T> {
 }
 
 impl<T> SmpFrame<T> {
-    /// Encode the frame to bytes using the given encode_payload handler.  
+    /// Encode the frame to bytes using the given payload codec.  
     /// For the common CBOR serialisation, see [SmpFrame::encode_with_cbor]
-    pub fn encode<E, R: AsRef<[u8]>>(
+    pub fn encode<C: EncodePayload<T>>(&self) -> Result<Vec<u8>, SmpError> {
+        self.encode_as::<C>(self.version, self.sequence)
+    }
+
+    /// Encode the frame with a different protocol version and sequence number,
+    /// used by transports that negotiate the version and assign sequence numbers.
+    pub(crate) fn encode_as<C: EncodePayload<T>>(
         &self,
-        encode_payload: impl FnOnce(&T) -> Result<R, E>,
-    ) -> Result<Vec<u8>, E> {
+        version: SmpVersion,
+        sequence: u8,
+    ) -> Result<Vec<u8>, SmpError> {
         let mut buf: Vec<u8> = Vec::with_capacity(12);
 
-        let encoded = encode_payload(&self.data)?;
-        let data: &[u8] = encoded.as_ref();
-
-        buf.push(self.operation.into());
-        buf.push(self.flags);
-        buf.extend_from_slice(&(data.len() as u16).to_be_bytes());
-        let group: u16 = self.group.into();
-        buf.extend_from_slice(&group.to_be_bytes());
-        buf.push(self.sequence);
-        buf.push(self.command);
+        // length is filled in after encoding the payload
+        buf.extend_from_slice(&self.header_bytes(version, sequence, 0));
+        C::encode_payload(&self.data, &mut buf).map_err(SmpError::PayloadEncodingError)?;
 
-        buf.extend(data.iter());
+        let data_len = Self::data_len(buf.len() - SmpHeader::LEN)?;
+        buf[2..4].copy_from_slice(&data_len.to_be_bytes());
 
         Ok(buf)
     }
 
-    /// Decode the frame from bytes using the given decode_payload handler.  
-    /// For the common CBOR serialisation, see [SmpFrame::decode_with_cbor]
-    pub fn decode(
-        buf: &[u8],
-        decode_payload: impl FnOnce(&[u8]) -> Result<T, Box<dyn std::error::Error>>,
-    ) -> Result<SmpFrame<T>, SmpError> {
-        if buf.len() < 8 {
-            return Err(SmpError::InvalidFrame);
+    /// Encode the frame into `buf` without allocating and return the length of the frame.  
+    /// Fails with [SmpError::BufferTooSmall] if the frame doesn't fit.
+    ///
+    /// Allocation-free for codecs that override [EncodePayload::encode_payload_into],
+    /// which includes the CBOR and raw codecs.
+    pub fn encode_into<C: EncodePayload<T>>(&self, buf: &mut [u8]) -> Result<usize, SmpError> {
+        if buf.len() < SmpHeader::LEN {
+            return Err(SmpError::BufferTooSmall);
         }
 
-        let operation = OpCode::from(buf[0] & 0x07);
-        let group = Group::from(u16::from_be_bytes([buf[4], buf[5]]));
-        let data_len = u16::from_be_bytes([buf[2], buf[3]]);
-        let _flags = buf[1];
-        let sequence = buf[6];
-        let command = buf[7];
+        let (header, payload) = buf.split_at_mut(SmpHeader::LEN);
+        let data_len = C::encode_payload_into(&self.data, payload).map_err(|err| {
+            if err.is::<BufferTooSmall>() {
+                SmpError::BufferTooSmall
+            } else {
+                SmpError::PayloadEncodingError(err)
+            }
+        })?;
+        let data_len = Self::data_len(data_len)?;
+        header.copy_from_slice(&self.header_bytes(self.version, self.sequence, data_len));
+
+        Ok(SmpHeader::LEN + data_len as usize)
+    }
 
-        if buf.len() < (8 + data_len) as usize {
-            return Err(SmpError::InvalidFrame);
-        }
+    /// Write the encoded frame to `writer` and return the number of bytes written.
+    ///
+    /// The payload is encoded twice, once to determine its length and once to write it,
+    /// so nothing is buffered for codecs that override [EncodePayload::write_payload].
+    #[cfg(feature = "std")]
+    pub fn encode_to<C: EncodePayload<T>>(
+        &self,
+        mut writer: impl std::io::Write,
+    ) -> Result<usize, SmpError> {
+        let mut counter = ByteCounter(0);
+        C::write_payload(&self.data, &mut counter).map_err(SmpError::PayloadEncodingError)?;
+        let data_len = Self::data_len(counter.0)?;
 
-        let data_buf = &buf[8..(8 + data_len as usize)];
-        let data = decode_payload(data_buf)?;
+        writer.write_all(&self.header_bytes(self.version, self.sequence, data_len))?;
+        C::write_payload(&self.data, &mut writer).map_err(SmpError::PayloadEncodingError)?;
 
-        Ok(SmpFrame::new(operation, sequence, group, command, data))
+        Ok(SmpHeader::LEN + data_len as usize)
+    }
+
+    fn header_bytes(&self, version: SmpVersion, sequence: u8, data_len: u16) -> [u8; 8] {
+        let version: u8 = version.into();
+        let [len_hi, len_lo] = data_len.to_be_bytes();
+        let [group_hi, group_lo] = u16::from(self.group).to_be_bytes();
+        [
+            u8::from(self.operation) | (version << 3),
+            self.flags,
+            len_hi,
+            len_lo,
+            group_hi,
+            group_lo,
+            sequence,
+            self.command,
+        ]
+    }
+
+    fn data_len(len: usize) -> Result<u16, SmpError> {
+        u16::try_from(len).map_err(|_| SmpError::PayloadTooLarge(len))
+    }
+
+    /// Decode the frame from bytes using the given payload codec.  
+    /// For the common CBOR serialisation, see [SmpFrame::decode_with_cbor]
+    pub fn decode<C: DecodePayload<T>>(buf: &[u8]) -> Result<SmpFrame<T>, SmpError> {
+        let header = SmpHeader::parse(buf)?;
+        let data = C::decode_payload(header.payload()).map_err(SmpError::PayloadDecodingError)?;
+
+        Ok(SmpFrame {
+            operation: header.operation(),
+            version: header.version(),
+            flags: header.flags(),
+            group: header.group(),
+            sequence: header.sequence(),
+            command: header.command(),
+            data,
+        })
     }
 }
 
@@ -174,16 +383,29 @@ impl<T> SmpFrame<T> {
 impl<T: serde::Serialize> SmpFrame<T> {
     /// Encode the frame to bytes using CBOR serialization.  
     /// This method requires Serde
-    pub fn encode_with_cbor(&self) -> Vec<u8> {
-        // buf cannot run out of space because it can allocate
-        self.encode(|data| {
-            let mut buf = Vec::new();
-            match ciborium::ser::into_writer(data, &mut buf) {
-                Ok(_) => Ok(buf),
-                Err(e) => Err(e),
-            }
-        })
-        .unwrap()
+    pub fn encode_with_cbor(&self) -> Result<Vec<u8>, SmpError> {
+        self.encode::<CborCodec>()
+    }
+
+    /// Encode the frame into `buf` using CBOR serialization, see [SmpFrame::encode_into]
+    pub fn encode_into_with_cbor(&self, buf: &mut [u8]) -> Result<usize, SmpError> {
+        self.encode_into::<CborCodec>(buf)
+    }
+}
+
+/// Counts the bytes written to it, used to determine the payload length before writing
+#[cfg(feature = "std")]
+struct ByteCounter(usize);
+
+#[cfg(feature = "std")]
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
     }
 }
 
@@ -192,9 +414,6 @@ impl<T: serde::de::DeserializeOwned> SmpFrame<T> {
     /// Decode the frame to bytes using CBOR deserialization.  
     /// This method requires Serde
     pub fn decode_with_cbor(buf: &[u8]) -> Result<SmpFrame<T>, SmpError> {
-        Self::decode(buf, |buf| {
-            let x: T = ciborium::de::from_reader(buf)?;
-            Ok(x)
-        })
+        Self::decode::<CborCodec>(buf)
     }
 }
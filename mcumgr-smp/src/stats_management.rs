@@ -0,0 +1,88 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::response::SmpResponse;
+use crate::{Group, OpCode, SmpFrame, SmpRequest};
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+pub enum StatsManagementCommand {
+    Read,
+    List,
+    Unknown(u8),
+}
+
+impl StatsManagementCommand {
+    pub const fn id(&self) -> u8 {
+        match self {
+            StatsManagementCommand::Read => 0,
+            StatsManagementCommand::List => 1,
+            StatsManagementCommand::Unknown(n) => *n,
+        }
+    }
+}
+
+impl From<StatsManagementCommand> for u8 {
+    fn from(cmd: StatsManagementCommand) -> Self {
+        cmd.id()
+    }
+}
+
+impl From<u8> for StatsManagementCommand {
+    fn from(id: u8) -> Self {
+        match id {
+            0 => StatsManagementCommand::Read,
+            1 => StatsManagementCommand::List,
+            n => StatsManagementCommand::Unknown(n),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StatsReadRequest {
+    /// name of the statistics group
+    pub name: String,
+}
+
+impl SmpRequest for StatsReadRequest {
+    const OPERATION: OpCode = OpCode::ReadRequest;
+    const GROUP: Group = Group::Statistics;
+    const COMMAND: u8 = StatsManagementCommand::Read.id();
+    type Response = StatsReadResponse;
+}
+
+pub fn read_stats(sequence: u8, name: String) -> SmpFrame<StatsReadRequest> {
+    StatsReadRequest { name }.into_frame(sequence)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StatsReadResponse {
+    pub name: String,
+    pub fields: BTreeMap<String, u64>,
+}
+
+pub type StatsReadResult = SmpResponse<StatsReadResponse>;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StatsListRequest {}
+
+impl SmpRequest for StatsListRequest {
+    const OPERATION: OpCode = OpCode::ReadRequest;
+    const GROUP: Group = Group::Statistics;
+    const COMMAND: u8 = StatsManagementCommand::List.id();
+    type Response = StatsListResponse;
+}
+
+pub fn list_stats(sequence: u8) -> SmpFrame<StatsListRequest> {
+    StatsListRequest {}.into_frame(sequence)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StatsListResponse {
+    /// names of the statistics groups
+    pub stat_list: Vec<String>,
+}
+
+pub type StatsListResult = SmpResponse<StatsListResponse>;
@@ -1,6 +1,10 @@
 // Author: Egor Markov <mark_ee@live.com>
 
-use super::{error::Error, smp::SmpTransportAsync};
+use super::{
+    error::Error,
+    fragment::{fragments, FrameAssembler},
+    smp::SmpTransportAsync,
+};
 use async_trait::async_trait;
 use btleplug::{
     api::{Central, Characteristic, Manager as _, Peripheral as _, ScanFilter},
@@ -13,10 +17,20 @@ use uuid::{uuid, Uuid};
 
 pub const SMP_CHAR: Uuid = uuid!("DA2E7828-FBCE-4E01-AE9E-261174997C48");
 
+/// ATT MTU every BLE connection supports before a larger one is negotiated
+pub const DEFAULT_ATT_MTU: u16 = 23;
+/// ATT opcode and handle preceding the value of a write or notification
+const ATT_HEADER_LEN: u16 = 3;
+
 pub struct BleTransport {
     peripheral_device: Peripheral,
     smp_char: Characteristic,
     notifications: Pin<Box<dyn Stream<Item = btleplug::api::ValueNotification> + Send>>,
+    /// responses larger than the MTU are split across notifications
+    assembler: FrameAssembler,
+    /// max. size of a single write, see [BleTransport::max_chunk_size]
+    max_chunk: usize,
+    timeout: Option<Duration>,
 }
 
 impl BleTransport {
@@ -72,9 +86,53 @@ impl BleTransport {
             peripheral_device,
             notifications,
             smp_char,
+            assembler: FrameAssembler::new(),
+            max_chunk: (DEFAULT_ATT_MTU - ATT_HEADER_LEN) as usize,
+            timeout: None,
         })
     }
 
+    /// Max. number of bytes written at once, frames larger than this are split into several writes.
+    ///
+    /// btleplug doesn't report the negotiated MTU, so this starts at the size allowed
+    /// by the default MTU and grows to the largest notification received from the device,
+    /// which is limited by the same MTU. Use [BleTransport::set_mtu] if the MTU is known.
+    pub fn max_chunk_size(&self) -> usize {
+        self.max_chunk
+    }
+
+    /// Set the negotiated ATT MTU
+    pub fn set_mtu(&mut self, mtu: u16) {
+        self.max_chunk = mtu.max(DEFAULT_ATT_MTU).saturating_sub(ATT_HEADER_LEN) as usize;
+    }
+
+    /// Fail a receive with [Error::Timeout] if no complete frame arrives in time.
+    /// `None` waits forever.
+    pub fn recv_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    async fn receive_frame(&mut self) -> Result<Vec<u8>, Error> {
+        loop {
+            if let Some(frame) = self.assembler.next_frame() {
+                return Ok(frame);
+            }
+
+            match self.notifications.next().await {
+                Some(res) if res.uuid == SMP_CHAR => {
+                    self.max_chunk = self.max_chunk.max(res.value.len());
+                    self.assembler.push(&res.value);
+                }
+                Some(_) => continue,
+                None => {
+                    return Err(Error::BLE(btleplug::Error::RuntimeError(String::from(
+                        "Notification stream error",
+                    ))));
+                }
+            }
+        }
+    }
+
     /// A bit more flexible than new()
     /// Allows user to perform scan with additional parameters,
     /// implemented by himself. For example - Scan filtering by the list of
@@ -96,6 +154,9 @@ impl BleTransport {
             peripheral_device: device,
             notifications,
             smp_char,
+            assembler: FrameAssembler::new(),
+            max_chunk: (DEFAULT_ATT_MTU - ATT_HEADER_LEN) as usize,
+            timeout: None,
         })
     }
 }
@@ -103,27 +164,32 @@ impl BleTransport {
 #[async_trait]
 impl SmpTransportAsync for BleTransport {
     async fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
-        self.peripheral_device
-            .write(
-                &self.smp_char,
-                &frame,
-                btleplug::api::WriteType::WithoutResponse,
-            )
-            .await?;
+        // drop the rest of a response that wasn't received completely
+        self.assembler.reset();
+
+        // the device reassembles the frame using the length in the header
+        for chunk in fragments(&frame, self.max_chunk) {
+            self.peripheral_device
+                .write(
+                    &self.smp_char,
+                    chunk,
+                    btleplug::api::WriteType::WithoutResponse,
+                )
+                .await?;
+        }
         Ok(())
     }
 
     async fn receive(&mut self) -> Result<Vec<u8>, Error> {
-        loop {
-            match self.notifications.next().await {
-                Some(res) if res.uuid == SMP_CHAR => return Ok(res.value),
-                Some(_) => continue,
-                None => {
-                    return Err(Error::BLE(btleplug::Error::RuntimeError(String::from(
-                        "Notification stream error",
-                    ))));
-                }
-            }
+        let res = match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.receive_frame())
+                .await
+                .unwrap_or(Err(Error::Timeout)),
+            None => self.receive_frame().await,
+        };
+        if res.is_err() {
+            self.assembler.reset();
         }
+        res
     }
 }
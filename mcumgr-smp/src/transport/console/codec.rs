@@ -0,0 +1,121 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use super::ConsoleOutputHandler;
+use crate::transport::error::Error;
+use crate::transport::smp_framing::{
+    ConsoleInput, SmpTransportDecoder, SmpTransportEncoder, DEFAULT_LINE_LENGTH,
+};
+use bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// [tokio_util::codec] for the SMP console framing, to use any `AsyncRead`/`AsyncWrite`
+/// as a `Framed` stream and sink of complete frames.
+///
+/// Other console output between the frames is skipped or passed to the handler set with
+/// [on_console_output](Self::on_console_output).
+///
+/// An invalid frame, e.g. with a wrong CRC, is returned as an error, which ends a `Framed`
+/// stream; it has to be created again to continue reading. To skip invalid frames instead,
+/// set a handler with [on_invalid_frame](Self::on_invalid_frame).
+///
+/// ```no_run
+/// # async fn example() -> Result<(), mcumgr_smp::transport::error::Error> {
+/// use futures::{SinkExt, StreamExt};
+/// use mcumgr_smp::transport::console::SmpConsoleCodec;
+/// use tokio_util::codec::Framed;
+///
+/// let stream = tokio::net::TcpStream::connect("localhost:4000").await?;
+/// let mut framed = Framed::new(stream, SmpConsoleCodec::new());
+///
+/// let request = mcumgr_smp::os_management::echo(0, "hi".into()).encode_with_cbor()?;
+/// framed.send(request).await?;
+/// let response = framed.next().await;
+/// # Ok(())
+/// # }
+/// ```
+pub struct SmpConsoleCodec {
+    decoder: SmpTransportDecoder,
+    line_length: usize,
+    console_output: Option<ConsoleOutputHandler>,
+    invalid_frame: Option<Box<dyn FnMut(Error) + Send>>,
+}
+
+impl Default for SmpConsoleCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SmpConsoleCodec {
+    pub fn new() -> Self {
+        Self {
+            decoder: SmpTransportDecoder::new(),
+            line_length: DEFAULT_LINE_LENGTH,
+            console_output: None,
+            invalid_frame: None,
+        }
+    }
+
+    /// Limit encoded lines to `line_length` bytes including the newline, see
+    /// [SmpTransportEncoder::with_line_length]
+    pub fn with_line_length(mut self, line_length: usize) -> Self {
+        self.line_length = line_length;
+        self
+    }
+
+    /// Call `handler` with each line of console output received between frames
+    pub fn on_console_output(mut self, handler: impl FnMut(&[u8]) + Send + 'static) -> Self {
+        self.console_output = Some(Box::new(handler));
+        self
+    }
+
+    /// Skip invalid frames and call `handler` with the error instead of returning it
+    pub fn on_invalid_frame(mut self, handler: impl FnMut(Error) + Send + 'static) -> Self {
+        self.invalid_frame = Some(Box::new(handler));
+        self
+    }
+}
+
+impl Decoder for SmpConsoleCodec {
+    type Item = Vec<u8>;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        // the decoder keeps partial lines and frames across calls
+        self.decoder.push(src);
+        src.clear();
+
+        while let Some(input) = self.decoder.next_input() {
+            match input {
+                Ok(ConsoleInput::Frame(frame)) => return Ok(Some(frame)),
+                Ok(ConsoleInput::Text(text)) => {
+                    if let Some(handler) = &mut self.console_output {
+                        handler(&text);
+                    }
+                }
+                Err(err) => match &mut self.invalid_frame {
+                    Some(handler) => handler(err.into()),
+                    None => return Err(err.into()),
+                },
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<T: AsRef<[u8]>> Encoder<T> for SmpConsoleCodec {
+    type Error = Error;
+
+    fn encode(&mut self, frame: T, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let mut encoder =
+            SmpTransportEncoder::new(frame.as_ref()).with_line_length(self.line_length);
+
+        while !encoder.is_complete() {
+            let start = dst.len();
+            dst.resize(start + encoder.line_length(), 0);
+            let len = encoder.write_line(&mut dst[start..])?;
+            dst.truncate(start + len);
+        }
+        Ok(())
+    }
+}
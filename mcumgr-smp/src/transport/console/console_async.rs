@@ -0,0 +1,184 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use super::pacing::{Pacer, Pacing};
+use super::ConsoleOutputHandler;
+use crate::transport::error::Error;
+use crate::transport::smp::SmpTransportAsync;
+use crate::transport::smp_framing::{ConsoleInput, SmpTransportDecoder};
+use async_trait::async_trait;
+use std::io;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpStream, ToSocketAddrs};
+use tokio::time::Instant;
+
+/// Transport using the SMP console framing over any async byte stream,
+/// e.g. a TCP connection to an emulator's serial port.
+///
+/// Other console output, e.g. log messages, is skipped or passed to
+/// the handler set with [on_console_output](Self::on_console_output).
+/// Invalid frames, e.g. with a wrong CRC, are skipped or passed to
+/// the handler set with [on_invalid_frame](Self::on_invalid_frame).
+///
+/// Receiving is cancel safe: if the future returned by `receive` is dropped, e.g. in
+/// `tokio::select!`, the partially received frame is kept and the next call continues with it.
+///
+/// Devices with small receive buffers may need shorter lines or pauses between lines and frames,
+/// see [set_pacing](Self::set_pacing).
+pub struct ConsoleTransportAsync<S> {
+    stream: BufReader<S>,
+    timeout: Option<Duration>,
+    buf: Vec<u8>,
+    decoder: SmpTransportDecoder,
+    console_output: Option<ConsoleOutputHandler>,
+    invalid_frame: Option<Box<dyn FnMut(Error) + Send>>,
+    pacer: Pacer,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send> ConsoleTransportAsync<S> {
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+            timeout: None,
+            buf: Vec::new(),
+            decoder: SmpTransportDecoder::new(),
+            console_output: None,
+            invalid_frame: None,
+            pacer: Pacer::default(),
+        }
+    }
+
+    /// Pace sending for devices with small receive buffers, see [Pacing]
+    pub fn set_pacing(&mut self, pacing: Pacing) {
+        self.pacer.pacing = pacing;
+    }
+
+    pub fn pacing(&self) -> Pacing {
+        self.pacer.pacing
+    }
+
+    /// Call `handler` with each line of console output received between frames
+    pub fn on_console_output(&mut self, handler: impl FnMut(&[u8]) + Send + 'static) {
+        self.console_output = Some(Box::new(handler));
+    }
+
+    /// Call `handler` with the error of each skipped invalid frame, e.g. one with a wrong CRC
+    pub fn on_invalid_frame(&mut self, handler: impl FnMut(Error) + Send + 'static) {
+        self.invalid_frame = Some(Box::new(handler));
+    }
+
+    pub fn get_ref(&self) -> &S {
+        self.stream.get_ref()
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        self.stream.get_mut()
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+
+    /// Fail a receive with [Error::Timeout] if no complete frame arrives in time.
+    /// `None` waits forever.
+    pub fn recv_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Drop a partially received frame
+    pub fn reset(&mut self) {
+        self.decoder.reset();
+    }
+
+    async fn receive_response(&mut self) -> Result<Vec<u8>, Error> {
+        match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.receive_frame())
+                .await
+                .map_err(|_| Error::Timeout)?,
+            None => self.receive_frame().await,
+        }
+    }
+
+    async fn receive_frame(&mut self) -> Result<Vec<u8>, Error> {
+        loop {
+            while let Some(input) = self.decoder.next_input() {
+                match input {
+                    Ok(ConsoleInput::Frame(frame)) => return Ok(frame),
+                    Ok(ConsoleInput::Text(text)) => {
+                        if let Some(handler) = &mut self.console_output {
+                            handler(&text);
+                        }
+                    }
+                    Err(err) => {
+                        if let Some(handler) = &mut self.invalid_frame {
+                            handler(err.into());
+                        }
+                    }
+                }
+            }
+
+            // the data is only consumed once it is in the decoder, so dropping the future loses nothing
+            let data = self.stream.fill_buf().await?;
+            if data.is_empty() {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+            }
+            let len = data.len();
+            self.decoder.push(data);
+            self.stream.consume(len);
+        }
+    }
+}
+
+impl ConsoleTransportAsync<TcpStream> {
+    /// Connect to a TCP server, e.g. QEMU or Renode serial ports backed by a socket
+    /// or an RTT bridge of a debug probe
+    pub async fn connect_tcp<A: ToSocketAddrs>(addr: A) -> Result<Self, io::Error> {
+        let stream = TcpStream::connect(addr).await?;
+        stream.set_nodelay(true)?;
+        Ok(Self::new(stream))
+    }
+}
+
+#[cfg(unix)]
+impl ConsoleTransportAsync<tokio::net::UnixStream> {
+    /// Connect to a Unix stream socket
+    pub async fn connect_unix<P: AsRef<std::path::Path>>(path: P) -> Result<Self, io::Error> {
+        Ok(Self::new(tokio::net::UnixStream::connect(path).await?))
+    }
+}
+
+#[cfg(unix)]
+impl ConsoleTransportAsync<super::PtyStream> {
+    /// Open a pseudo terminal, e.g. the UART of a Zephyr `native_sim` build.
+    /// The terminal has to be in raw mode, as set up by `native_sim`.
+    pub async fn open_pty<P: AsRef<std::path::Path>>(path: P) -> Result<Self, io::Error> {
+        Ok(Self::new(super::PtyStream::open(path)?))
+    }
+}
+
+#[async_trait]
+impl<S: AsyncRead + AsyncWrite + Unpin + Send> SmpTransportAsync for ConsoleTransportAsync<S> {
+    async fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
+        tokio::time::sleep(self.pacer.frame_delay(Instant::now().into_std())).await;
+
+        let mut encoder = self.pacer.encoder(&frame);
+
+        self.buf.resize(encoder.line_length(), 0);
+        let stream = self.stream.get_mut();
+        while !encoder.is_complete() {
+            let len = encoder.write_line(&mut self.buf)?;
+            stream.write_all(&self.buf[0..len]).await?;
+            if let Some(delay) = self.pacer.line_delay(&encoder) {
+                stream.flush().await?;
+                tokio::time::sleep(delay).await;
+            }
+        }
+        stream.flush().await?;
+
+        Ok(())
+    }
+
+    async fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        self.receive_response().await
+    }
+}
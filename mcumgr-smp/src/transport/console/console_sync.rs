@@ -0,0 +1,171 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use super::pacing::{Pacer, Pacing};
+use super::ConsoleOutputHandler;
+use crate::transport::error::Error;
+use crate::transport::smp::SmpTransport;
+use crate::transport::smp_framing::{ConsoleInput, SmpTransportDecoder};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Instant;
+
+/// Transport using the SMP console framing over any byte stream,
+/// e.g. a TCP connection to an emulator's serial port.
+///
+/// Other console output, e.g. log messages, is skipped or passed to
+/// the handler set with [on_console_output](Self::on_console_output).
+/// Invalid frames, e.g. with a wrong CRC, are skipped or passed to
+/// the handler set with [on_invalid_frame](Self::on_invalid_frame).
+///
+/// Devices with small receive buffers may need shorter lines or pauses between lines and frames,
+/// see [set_pacing](Self::set_pacing).
+pub struct ConsoleTransport<S: Read + Write> {
+    stream: BufReader<S>,
+    buf: Vec<u8>,
+    decoder: SmpTransportDecoder,
+    console_output: Option<ConsoleOutputHandler>,
+    invalid_frame: Option<Box<dyn FnMut(Error) + Send>>,
+    pacer: Pacer,
+}
+
+impl<S: Read + Write> ConsoleTransport<S> {
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+            buf: Vec::new(),
+            decoder: SmpTransportDecoder::new(),
+            console_output: None,
+            invalid_frame: None,
+            pacer: Pacer::default(),
+        }
+    }
+
+    /// Pace sending for devices with small receive buffers, see [Pacing]
+    pub fn set_pacing(&mut self, pacing: Pacing) {
+        self.pacer.pacing = pacing;
+    }
+
+    pub fn pacing(&self) -> Pacing {
+        self.pacer.pacing
+    }
+
+    fn receive_frame(&mut self) -> Result<Vec<u8>, Error> {
+        loop {
+            while let Some(input) = self.decoder.next_input() {
+                match input {
+                    Ok(ConsoleInput::Frame(frame)) => return Ok(frame),
+                    Ok(ConsoleInput::Text(text)) => {
+                        if let Some(handler) = &mut self.console_output {
+                            handler(&text);
+                        }
+                    }
+                    Err(err) => {
+                        if let Some(handler) = &mut self.invalid_frame {
+                            handler(err.into());
+                        }
+                    }
+                }
+            }
+
+            let data = match self.stream.fill_buf() {
+                Ok(data) => data,
+                // read timeout of the stream, e.g. `SerialTransport::recv_timeout`
+                Err(err)
+                    if matches!(
+                        err.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    return Err(Error::Timeout)
+                }
+                Err(err) => return Err(err.into()),
+            };
+            if data.is_empty() {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+            }
+            let len = data.len();
+            self.decoder.push(data);
+            self.stream.consume(len);
+        }
+    }
+
+    /// Call `handler` with each line of console output received between frames
+    pub fn on_console_output(&mut self, handler: impl FnMut(&[u8]) + Send + 'static) {
+        self.console_output = Some(Box::new(handler));
+    }
+
+    /// Call `handler` with the error of each skipped invalid frame, e.g. one with a wrong CRC
+    pub fn on_invalid_frame(&mut self, handler: impl FnMut(Error) + Send + 'static) {
+        self.invalid_frame = Some(Box::new(handler));
+    }
+
+    pub fn get_ref(&self) -> &S {
+        self.stream.get_ref()
+    }
+
+    /// Access the stream, e.g. to set timeouts
+    pub fn get_mut(&mut self) -> &mut S {
+        self.stream.get_mut()
+    }
+
+    pub fn into_inner(self) -> S {
+        self.stream.into_inner()
+    }
+}
+
+impl ConsoleTransport<TcpStream> {
+    /// Connect to a TCP server, e.g. QEMU or Renode serial ports backed by a socket
+    /// or an RTT bridge of a debug probe
+    pub fn connect_tcp<A: ToSocketAddrs>(addr: A) -> Result<Self, io::Error> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        Ok(Self::new(stream))
+    }
+}
+
+#[cfg(unix)]
+impl ConsoleTransport<std::os::unix::net::UnixStream> {
+    /// Connect to a Unix stream socket
+    pub fn connect_unix<P: AsRef<std::path::Path>>(path: P) -> Result<Self, io::Error> {
+        Ok(Self::new(std::os::unix::net::UnixStream::connect(path)?))
+    }
+}
+
+#[cfg(unix)]
+impl ConsoleTransport<std::fs::File> {
+    /// Open a pseudo terminal, e.g. the UART of a Zephyr `native_sim` build.
+    /// The terminal has to be in raw mode, as set up by `native_sim`.
+    pub fn open_pty<P: AsRef<std::path::Path>>(path: P) -> Result<Self, io::Error> {
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)?;
+        Ok(Self::new(file))
+    }
+}
+
+impl<S: Read + Write> SmpTransport for ConsoleTransport<S> {
+    fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
+        std::thread::sleep(self.pacer.frame_delay(Instant::now()));
+
+        let mut encoder = self.pacer.encoder(&frame);
+
+        self.buf.resize(encoder.line_length(), 0);
+        let stream = self.stream.get_mut();
+        while !encoder.is_complete() {
+            let len = encoder.write_line(&mut self.buf)?;
+            stream.write_all(&self.buf[0..len])?;
+            if let Some(delay) = self.pacer.line_delay(&encoder) {
+                stream.flush()?;
+                std::thread::sleep(delay);
+            }
+        }
+        stream.flush()?;
+
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        self.receive_frame()
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+#[cfg(feature = "console-codec")]
+pub mod codec;
+#[cfg(feature = "console-codec")]
+pub use codec::SmpConsoleCodec;
+
+#[cfg(feature = "transport-console-async")]
+pub mod console_async;
+#[cfg(feature = "transport-console-async")]
+pub use console_async::ConsoleTransportAsync;
+
+#[cfg(all(unix, feature = "transport-console-async"))]
+pub mod pty;
+#[cfg(all(unix, feature = "transport-console-async"))]
+pub use pty::PtyStream;
+
+#[cfg(any(feature = "transport-console", feature = "transport-console-async"))]
+pub mod pacing;
+#[cfg(any(feature = "transport-console", feature = "transport-console-async"))]
+pub use pacing::Pacing;
+
+#[cfg(feature = "transport-console")]
+pub mod console_sync;
+#[cfg(feature = "transport-console")]
+pub use console_sync::ConsoleTransport;
+
+/// Receives the console output between frames, see `on_console_output`
+pub type ConsoleOutputHandler = Box<dyn FnMut(&[u8]) + Send>;
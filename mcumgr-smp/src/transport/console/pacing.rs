@@ -0,0 +1,58 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::transport::smp_framing::{SmpTransportEncoder, DEFAULT_LINE_LENGTH};
+use std::time::{Duration, Instant};
+
+/// How frames are sent to devices with small UART receive buffers,
+/// see `set_pacing` of the console and serial transports.
+///
+/// There is no option to hold back frames until the previous one is answered:
+/// `CborSmpTransport::request` already waits for each response before sending the next frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pacing {
+    /// Maximum length of the sent lines including the newline, e.g. the device's
+    /// `CONFIG_UART_MCUMGR_RX_BUF_SIZE`. Defaults to [DEFAULT_LINE_LENGTH].
+    pub line_length: usize,
+    /// Pause between the lines of a frame
+    pub line_delay: Duration,
+    /// Minimum time between the start of two frames
+    pub frame_delay: Duration,
+}
+
+impl Default for Pacing {
+    fn default() -> Self {
+        Self {
+            line_length: DEFAULT_LINE_LENGTH,
+            line_delay: Duration::ZERO,
+            frame_delay: Duration::ZERO,
+        }
+    }
+}
+
+/// The [Pacing] state of a transport, shared by the sync and async console transports
+#[derive(Default)]
+pub(crate) struct Pacer {
+    pub(crate) pacing: Pacing,
+    last_frame: Option<Instant>,
+}
+
+impl Pacer {
+    /// Time to wait before sending the next frame at `now`, the frame counts as sent after it
+    pub(crate) fn frame_delay(&mut self, now: Instant) -> Duration {
+        let delay = self.last_frame.map_or(Duration::ZERO, |last_frame| {
+            (last_frame + self.pacing.frame_delay).saturating_duration_since(now)
+        });
+        self.last_frame = Some(now + delay);
+        delay
+    }
+
+    pub(crate) fn encoder<'a>(&self, frame: &'a [u8]) -> SmpTransportEncoder<'a> {
+        SmpTransportEncoder::new(frame).with_line_length(self.pacing.line_length)
+    }
+
+    /// Pause after the line just written by `encoder`, if any
+    pub(crate) fn line_delay(&self, encoder: &SmpTransportEncoder) -> Option<Duration> {
+        (!self.pacing.line_delay.is_zero() && !encoder.is_complete())
+            .then_some(self.pacing.line_delay)
+    }
+}
@@ -4,9 +4,21 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("SMP: {0}")]
     Smp(#[from] crate::smp::SmpError),
-    #[cfg(feature = "transport-serial")]
+    #[error("Device: {0}")]
+    Device(#[from] crate::response::ResponseError),
+    /// No response arrived in time, including all retransmissions
+    #[error("timed out waiting for a response")]
+    Timeout,
+    /// A received datagram didn't fit into the receive buffer of the given size
+    #[cfg(any(feature = "transport-udp", feature = "transport-udp-async"))]
+    #[error("datagram larger than the receive buffer of {0} bytes")]
+    Truncated(usize),
+    #[cfg(feature = "console-framing")]
     #[error("SmpTransport: {0}")]
     SmpTransport(#[from] super::smp_framing::SmpTransportError),
+    #[cfg(feature = "transport-mock")]
+    #[error("Mock transport: {0}")]
+    Mock(String),
     #[cfg(feature = "transport-ble-async")]
     #[error("Bluetooth transport: {0}")]
     BLE(#[from] btleplug::Error),
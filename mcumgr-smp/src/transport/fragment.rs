@@ -0,0 +1,75 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::SmpHeader;
+use alloc::vec::Vec;
+use core::slice::Chunks;
+
+/// Split a frame into packets of at most `max_len` bytes, e.g. writes limited by the ATT MTU.
+///
+/// The receiver reassembles them using the length in the SMP header, see [FrameAssembler].
+pub fn fragments(frame: &[u8], max_len: usize) -> Chunks<'_, u8> {
+    frame.chunks(max_len.max(1))
+}
+
+/// Reassembles frames that arrive split across several packets, e.g. BLE notifications
+/// limited by the ATT MTU.
+///
+/// The length field of the SMP header tells how many bytes belong to the frame.
+/// Data following a complete frame is kept as the start of the next one.
+///
+/// ```
+/// use mcumgr_smp::transport::fragment::FrameAssembler;
+///
+/// let frame = [1, 0, 0, 4, 0, 0, 0, 0, 0xa1, 0x61, 0x72, 0x60];
+/// let mut assembler = FrameAssembler::new();
+///
+/// assembler.push(&frame[..5]);
+/// assert_eq!(assembler.next_frame(), None);
+/// assembler.push(&frame[5..]);
+/// assert_eq!(assembler.next_frame(), Some(frame.to_vec()));
+/// ```
+#[derive(Debug, Default)]
+pub struct FrameAssembler {
+    buf: Vec<u8>,
+}
+
+impl FrameAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append received data
+    pub fn push(&mut self, data: &[u8]) {
+        self.buf.extend_from_slice(data);
+    }
+
+    /// Take the next complete frame, if all of it was received
+    pub fn next_frame(&mut self) -> Option<Vec<u8>> {
+        let len = self.frame_len()?;
+        if self.buf.len() < len {
+            return None;
+        }
+
+        let rest = self.buf.split_off(len);
+        Some(core::mem::replace(&mut self.buf, rest))
+    }
+
+    /// Length of the frame being received, if its header is complete
+    pub fn frame_len(&self) -> Option<usize> {
+        let len = self.buf.get(2..4)?;
+        if self.buf.len() < SmpHeader::LEN {
+            return None;
+        }
+        Some(SmpHeader::LEN + u16::from_be_bytes([len[0], len[1]]) as usize)
+    }
+
+    /// Number of buffered bytes of incomplete frames
+    pub fn pending(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Drop a partially received frame, e.g. after a timeout
+    pub fn reset(&mut self) {
+        self.buf.clear();
+    }
+}
@@ -0,0 +1,350 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::codec::RawCodec;
+use crate::transport::error::Error;
+use crate::transport::smp::SmpTransport;
+use crate::{Group, OpCode, SmpFrame, SmpHeader};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(feature = "payload-cbor")]
+use crate::{codec::CborCodec, codec::DecodePayload, codec::EncodePayload, SmpRequest};
+
+type PayloadMatcher = Box<dyn Fn(&[u8]) -> bool + Send>;
+
+/// A frame the [MockTransport] expects to be sent, and what it replies with
+pub struct Expectation {
+    operation: Option<OpCode>,
+    group: Option<Group>,
+    command: Option<u8>,
+    payload: Option<PayloadMatcher>,
+    replies: Vec<Reply>,
+}
+
+enum Reply {
+    /// response payload, sent in a frame matching the request
+    Payload(Vec<u8>),
+    Frame(Vec<u8>),
+    Error(Error),
+    Delay(Duration),
+}
+
+/// A reply resolved against the request it answers
+enum Pending {
+    Frame(Vec<u8>),
+    Error(Error),
+    Delay(Duration),
+}
+
+impl Expectation {
+    /// Expect any frame
+    pub fn frame() -> Self {
+        Self {
+            operation: None,
+            group: None,
+            command: None,
+            payload: None,
+            replies: Vec::new(),
+        }
+    }
+
+    /// Expect a request of type `R`, matching its operation, group and command
+    #[cfg(feature = "payload-cbor")]
+    pub fn request<R: SmpRequest>() -> Self {
+        Self::frame()
+            .operation(R::OPERATION)
+            .group(R::GROUP)
+            .command(R::COMMAND)
+    }
+
+    pub fn operation(mut self, operation: OpCode) -> Self {
+        self.operation = Some(operation);
+        self
+    }
+
+    pub fn group(mut self, group: Group) -> Self {
+        self.group = Some(group);
+        self
+    }
+
+    pub fn command(mut self, command: u8) -> Self {
+        self.command = Some(command);
+        self
+    }
+
+    /// Expect the encoded payload to be exactly `payload`
+    pub fn payload_bytes(self, payload: impl Into<Vec<u8>>) -> Self {
+        let payload = payload.into();
+        self.payload_matches(move |p| p == payload.as_slice())
+    }
+
+    /// Expect the encoded payload to fulfill `matcher`
+    pub fn payload_matches(mut self, matcher: impl Fn(&[u8]) -> bool + Send + 'static) -> Self {
+        self.payload = Some(Box::new(matcher));
+        self
+    }
+
+    /// Expect a CBOR payload that decodes to `T` and fulfills `matcher`
+    #[cfg(feature = "payload-cbor")]
+    pub fn payload<T>(self, matcher: impl Fn(&T) -> bool + Send + 'static) -> Self
+    where
+        CborCodec: DecodePayload<T>,
+    {
+        self.payload_matches(move |p| CborCodec::decode_payload(p).is_ok_and(|p| matcher(&p)))
+    }
+
+    /// Reply with a CBOR payload, in a response frame matching the request.
+    /// If the payload can't be encoded, the receive fails with [Error::Mock].
+    #[cfg(feature = "payload-cbor")]
+    pub fn respond<T>(mut self, payload: T) -> Self
+    where
+        CborCodec: EncodePayload<T>,
+    {
+        let mut buf = Vec::new();
+        let reply = match CborCodec::encode_payload(&payload, &mut buf) {
+            Ok(()) => Reply::Payload(buf),
+            Err(err) => Reply::Error(Error::Mock(format!(
+                "failed to encode mock response: {}",
+                err
+            ))),
+        };
+        self.replies.push(reply);
+        self
+    }
+
+    /// Reply with an error reported by the device
+    #[cfg(feature = "payload-cbor")]
+    pub fn respond_error(self, error: impl Into<crate::response::ResponseError>) -> Self {
+        self.respond(error.into())
+    }
+
+    /// Reply with an already encoded payload, in a response frame matching the request
+    pub fn respond_payload(mut self, payload: impl Into<Vec<u8>>) -> Self {
+        self.replies.push(Reply::Payload(payload.into()));
+        self
+    }
+
+    /// Reply with a complete frame, sent as is
+    pub fn respond_frame(mut self, frame: impl Into<Vec<u8>>) -> Self {
+        self.replies.push(Reply::Frame(frame.into()));
+        self
+    }
+
+    /// Fail the next receive with `error`
+    pub fn fail(mut self, error: Error) -> Self {
+        self.replies.push(Reply::Error(error));
+        self
+    }
+
+    /// Wait before the next reply
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.replies.push(Reply::Delay(delay));
+        self
+    }
+
+    /// Describes why `header` doesn't match, if it doesn't
+    fn mismatch(&self, header: &SmpHeader) -> Option<String> {
+        if let Some(operation) = self.operation {
+            if operation != header.operation() {
+                return Some(format!(
+                    "expected operation {:?}, got {:?}",
+                    operation,
+                    header.operation()
+                ));
+            }
+        }
+        if let Some(group) = self.group {
+            if group != header.group() {
+                return Some(format!(
+                    "expected group {:?}, got {:?}",
+                    group,
+                    header.group()
+                ));
+            }
+        }
+        if let Some(command) = self.command {
+            if command != header.command() {
+                return Some(format!(
+                    "expected command {}, got {}",
+                    command,
+                    header.command()
+                ));
+            }
+        }
+        if let Some(payload) = &self.payload {
+            if !payload(header.payload()) {
+                return Some(format!("unexpected payload {:02x?}", header.payload()));
+            }
+        }
+        None
+    }
+}
+
+#[derive(Default)]
+struct State {
+    expectations: VecDeque<Expectation>,
+    pending: VecDeque<Pending>,
+    sent: Vec<Vec<u8>>,
+    failures: Vec<String>,
+}
+
+/// A transport that checks the sent frames against a script of [Expectation]s
+/// and replies as scripted, for testing code built on the SMP transports.
+///
+/// The transport is a handle to shared state: keep a clone to add expectations
+/// and [verify](MockTransport::verify) the conversation after passing it to a `CborSmpTransport`.
+/// A receive without a scripted reply fails with [Error::Timeout], as if the device didn't answer.
+///
+/// ```
+/// use mcumgr_smp::os_management::{EchoRequest, EchoResponse};
+/// use mcumgr_smp::transport::mock::{Expectation, MockTransport};
+/// use mcumgr_smp::transport::smp::CborSmpTransport;
+///
+/// let mock = MockTransport::new();
+/// mock.expect(
+///     Expectation::request::<EchoRequest>()
+///         .payload(|req: &EchoRequest| req.d == "hello")
+///         .respond(EchoResponse { r: "hello".into() }),
+/// );
+///
+/// let mut transport = CborSmpTransport::new(Box::new(mock.clone()));
+/// let response = transport.request(EchoRequest { d: "hello".into() }).unwrap();
+/// assert_eq!(response.r, "hello");
+/// mock.verify();
+/// ```
+#[derive(Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<State>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an expectation to the script
+    pub fn expect(&self, expectation: Expectation) -> &Self {
+        self.state
+            .lock()
+            .unwrap()
+            .expectations
+            .push_back(expectation);
+        self
+    }
+
+    /// All frames sent so far
+    pub fn sent(&self) -> Vec<Vec<u8>> {
+        self.state.lock().unwrap().sent.clone()
+    }
+
+    /// Panics if a frame didn't match its expectation or not all expectations were met
+    pub fn verify(&self) {
+        let state = self.state.lock().unwrap();
+        if !state.failures.is_empty() {
+            panic!("unexpected frames:\n{}", state.failures.join("\n"));
+        }
+        if !state.expectations.is_empty() {
+            panic!("{} expected frame(s) not sent", state.expectations.len());
+        }
+    }
+
+    fn handle_send(&self, frame: Vec<u8>) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        state.sent.push(frame.clone());
+
+        let index = state.sent.len();
+        let header = match SmpHeader::parse(&frame) {
+            Ok(header) => header,
+            Err(err) => {
+                let failure = format!("frame {}: invalid frame: {}", index, err);
+                state.failures.push(failure.clone());
+                return Err(Error::Mock(failure));
+            }
+        };
+
+        let Some(expectation) = state.expectations.pop_front() else {
+            let failure = format!("frame {}: no more frames expected", index);
+            state.failures.push(failure.clone());
+            return Err(Error::Mock(failure));
+        };
+        if let Some(mismatch) = expectation.mismatch(&header) {
+            let failure = format!("frame {}: {}", index, mismatch);
+            state.failures.push(failure.clone());
+            return Err(Error::Mock(failure));
+        }
+
+        for reply in expectation.replies {
+            let pending = match reply {
+                Reply::Payload(payload) => Pending::Frame(response_frame(&header, payload)?),
+                Reply::Frame(frame) => Pending::Frame(frame),
+                Reply::Error(err) => Pending::Error(err),
+                Reply::Delay(delay) => Pending::Delay(delay),
+            };
+            state.pending.push_back(pending);
+        }
+        Ok(())
+    }
+
+    fn next_reply(&self) -> Pending {
+        self.state
+            .lock()
+            .unwrap()
+            .pending
+            .pop_front()
+            .unwrap_or(Pending::Error(Error::Timeout))
+    }
+}
+
+/// Build the response to `request` carrying `payload`
+fn response_frame(request: &SmpHeader, payload: Vec<u8>) -> Result<Vec<u8>, Error> {
+    let operation = match request.operation() {
+        OpCode::ReadRequest => OpCode::ReadResponse,
+        OpCode::WriteRequest => OpCode::WriteResponse,
+        op => op,
+    };
+    let frame = SmpFrame {
+        operation,
+        version: request.version(),
+        flags: 0,
+        group: request.group(),
+        sequence: request.sequence(),
+        command: request.command(),
+        data: payload,
+    };
+    Ok(frame.encode::<RawCodec>()?)
+}
+
+impl SmpTransport for MockTransport {
+    fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
+        self.handle_send(frame)
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        loop {
+            match self.next_reply() {
+                Pending::Frame(frame) => return Ok(frame),
+                Pending::Error(err) => return Err(err),
+                Pending::Delay(delay) => std::thread::sleep(delay),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl crate::transport::smp::SmpTransportAsync for MockTransport {
+    async fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
+        self.handle_send(frame)
+    }
+
+    async fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        loop {
+            match self.next_reply() {
+                Pending::Frame(frame) => return Ok(frame),
+                Pending::Error(err) => return Err(err),
+                Pending::Delay(delay) => tokio::time::sleep(delay).await,
+            }
+        }
+    }
+}
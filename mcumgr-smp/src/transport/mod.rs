@@ -1,13 +1,24 @@
 // Author: Sascha Zenglein <zenglein@gessler.de>
 // Copyright (c) 2023 Gessler GmbH.
 
+/// Console framed transport over any byte stream, e.g. TCP, Unix sockets or PTYs
+#[cfg(any(
+    feature = "transport-console",
+    feature = "transport-console-async",
+    feature = "console-codec"
+))]
+pub mod console;
+
 /// Serial transport implementation
-#[cfg(feature = "transport-serial")]
+#[cfg(any(feature = "transport-serial", feature = "transport-serial-async"))]
 pub mod serial;
 /// Support for the [SMP text console transport](https://github.com/apache/mynewt-mcumgr/blob/master/transport/smp-console.md)
-#[cfg(feature = "transport-serial")]
+#[cfg(feature = "console-framing")]
 pub mod smp_framing;
 
+/// Split and reassemble frames for transports with a small MTU
+pub mod fragment;
+
 /// UDP transport implementation
 #[cfg(any(feature = "transport-udp", feature = "transport-udp-async"))]
 pub mod udp;
@@ -16,6 +27,20 @@ pub mod udp;
 #[cfg(feature = "transport-ble-async")]
 pub mod ble;
 
+/// Scriptable transport for testing code built on the SMP transports
+#[cfg(feature = "transport-mock")]
+pub mod mock;
+
+/// Record sessions of any transport and replay them
+#[cfg(feature = "std")]
+pub mod record;
+
+/// pcapng capture of the frames of any transport
+#[cfg(feature = "std")]
+pub mod pcapng;
+
+#[cfg(feature = "std")]
 pub mod error;
 
+#[cfg(feature = "std")]
 pub mod smp;
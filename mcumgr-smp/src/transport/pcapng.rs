@@ -0,0 +1,243 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::transport::error::Error;
+use crate::transport::smp::SmpTransport;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// UDP port used for the capture, the SMP port Wireshark decodes as mcumgr
+pub const CAPTURE_PORT: u16 = 1337;
+
+const BLOCK_SECTION_HEADER: u32 = 0x0A0D_0D0A;
+const BLOCK_INTERFACE_DESCRIPTION: u32 = 1;
+const BLOCK_ENHANCED_PACKET: u32 = 6;
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+const LINKTYPE_RAW: u16 = 101;
+
+const OPT_END: u16 = 0;
+const OPT_IF_NAME: u16 = 2;
+const OPT_IF_DESCRIPTION: u16 = 3;
+const OPT_EPB_FLAGS: u16 = 2;
+const EPB_FLAGS_INBOUND: u32 = 0b01;
+const EPB_FLAGS_OUTBOUND: u32 = 0b10;
+
+/// Host and device addresses of the IPv4 packets the frames are wrapped in
+const HOST_ADDR: [u8; 4] = [127, 0, 0, 1];
+const DEVICE_ADDR: [u8; 4] = [127, 0, 0, 2];
+
+/// The transport a capture was taken on, stored as the interface name of the capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureLink {
+    Serial,
+    Udp,
+    Ble,
+    Other,
+}
+
+impl CaptureLink {
+    fn name(&self) -> &'static str {
+        match self {
+            CaptureLink::Serial => "smp-serial",
+            CaptureLink::Udp => "smp-udp",
+            CaptureLink::Ble => "smp-ble",
+            CaptureLink::Other => "smp",
+        }
+    }
+}
+
+/// Direction of a captured frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// sent to the device
+    Outbound,
+    /// received from the device
+    Inbound,
+}
+
+/// Writes SMP frames as a [pcapng](https://www.ietf.org/archive/id/draft-ietf-opsawg-pcapng-02.html) capture.
+///
+/// Each frame is wrapped in an IPv4/UDP packet between the host (127.0.0.1) and the device (127.0.0.2),
+/// both on port [CAPTURE_PORT], so Wireshark decodes it with its mcumgr dissector
+/// regardless of the actual transport. The direction is stored in the packet flags as well.
+pub struct PcapngWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> PcapngWriter<W> {
+    /// Write the section and interface headers
+    pub fn new(mut writer: W, link: CaptureLink) -> io::Result<Self> {
+        let mut shb = Vec::new();
+        shb.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+        shb.extend_from_slice(&1u16.to_le_bytes()); // major version
+        shb.extend_from_slice(&0u16.to_le_bytes()); // minor version
+        shb.extend_from_slice(&(-1i64).to_le_bytes()); // section length unknown
+        write_option(&mut shb, OPT_END, &[]);
+        write_block(&mut writer, BLOCK_SECTION_HEADER, &shb)?;
+
+        let mut idb = Vec::new();
+        idb.extend_from_slice(&LINKTYPE_RAW.to_le_bytes());
+        idb.extend_from_slice(&0u16.to_le_bytes()); // reserved
+        idb.extend_from_slice(&0u32.to_le_bytes()); // no snap length
+        write_option(&mut idb, OPT_IF_NAME, link.name().as_bytes());
+        write_option(
+            &mut idb,
+            OPT_IF_DESCRIPTION,
+            b"SMP frames wrapped in IPv4/UDP",
+        );
+        write_option(&mut idb, OPT_END, &[]);
+        write_block(&mut writer, BLOCK_INTERFACE_DESCRIPTION, &idb)?;
+
+        writer.flush()?;
+        Ok(Self { writer })
+    }
+
+    /// Write a frame, timestamped with the current time
+    pub fn write_frame(&mut self, direction: Direction, frame: &[u8]) -> io::Result<()> {
+        let packet = udp_packet(direction, frame);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+
+        let mut epb = Vec::with_capacity(packet.len() + 40);
+        epb.extend_from_slice(&0u32.to_le_bytes()); // interface id
+        epb.extend_from_slice(&((timestamp >> 32) as u32).to_le_bytes());
+        epb.extend_from_slice(&(timestamp as u32).to_le_bytes());
+        epb.extend_from_slice(&(packet.len() as u32).to_le_bytes()); // captured length
+        epb.extend_from_slice(&(packet.len() as u32).to_le_bytes()); // original length
+        epb.extend_from_slice(&packet);
+        pad(&mut epb);
+        let flags = match direction {
+            Direction::Inbound => EPB_FLAGS_INBOUND,
+            Direction::Outbound => EPB_FLAGS_OUTBOUND,
+        };
+        write_option(&mut epb, OPT_EPB_FLAGS, &flags.to_le_bytes());
+        write_option(&mut epb, OPT_END, &[]);
+        write_block(&mut self.writer, BLOCK_ENHANCED_PACKET, &epb)?;
+
+        // keep the capture readable if the program is killed
+        self.writer.flush()
+    }
+}
+
+fn pad(buf: &mut Vec<u8>) {
+    buf.resize(buf.len().next_multiple_of(4), 0);
+}
+
+fn write_option(buf: &mut Vec<u8>, code: u16, value: &[u8]) {
+    buf.extend_from_slice(&code.to_le_bytes());
+    buf.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    buf.extend_from_slice(value);
+    pad(buf);
+}
+
+fn write_block(writer: &mut impl Write, block_type: u32, body: &[u8]) -> io::Result<()> {
+    let len = (body.len() + 12) as u32;
+    writer.write_all(&block_type.to_le_bytes())?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(body)?;
+    writer.write_all(&len.to_le_bytes())
+}
+
+/// Wrap `payload` in IPv4 and UDP headers
+fn udp_packet(direction: Direction, payload: &[u8]) -> Vec<u8> {
+    let (src, dst) = match direction {
+        Direction::Outbound => (HOST_ADDR, DEVICE_ADDR),
+        Direction::Inbound => (DEVICE_ADDR, HOST_ADDR),
+    };
+    // frames too large for a datagram keep their data, only the length fields saturate
+    let udp_len = (payload.len() + 8).min(u16::MAX as usize) as u16;
+    let ip_len = (payload.len() + 28).min(u16::MAX as usize) as u16;
+
+    let mut packet = Vec::with_capacity(payload.len() + 28);
+    packet.extend_from_slice(&[0x45, 0]); // IPv4, 20 byte header
+    packet.extend_from_slice(&ip_len.to_be_bytes());
+    packet.extend_from_slice(&[0, 0, 0x40, 0]); // id, don't fragment
+    packet.extend_from_slice(&[64, 17, 0, 0]); // TTL, UDP, checksum
+    packet.extend_from_slice(&src);
+    packet.extend_from_slice(&dst);
+    let checksum = ip_checksum(&packet);
+    packet[10..12].copy_from_slice(&checksum.to_be_bytes());
+
+    packet.extend_from_slice(&CAPTURE_PORT.to_be_bytes());
+    packet.extend_from_slice(&CAPTURE_PORT.to_be_bytes());
+    packet.extend_from_slice(&udp_len.to_be_bytes());
+    packet.extend_from_slice(&[0, 0]); // no checksum
+    packet.extend_from_slice(payload);
+    packet
+}
+
+fn ip_checksum(header: &[u8]) -> u16 {
+    let mut sum: u32 = header
+        .chunks(2)
+        .map(|word| u16::from_be_bytes([word[0], word[1]]) as u32)
+        .sum();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// Wraps a transport and writes every sent and received frame to a pcapng capture,
+/// see [PcapngWriter]. Works with sync and async transports.
+pub struct PcapngTransport<T> {
+    inner: T,
+    writer: PcapngWriter<Box<dyn Write + Send>>,
+}
+
+impl<T> PcapngTransport<T> {
+    /// Capture to `writer`
+    pub fn new(
+        inner: T,
+        writer: impl Write + Send + 'static,
+        link: CaptureLink,
+    ) -> io::Result<Self> {
+        let writer: Box<dyn Write + Send> = Box::new(writer);
+        Ok(Self {
+            inner,
+            writer: PcapngWriter::new(writer, link)?,
+        })
+    }
+
+    /// Capture to a newly created file at `path`
+    pub fn create(inner: T, path: impl AsRef<Path>, link: CaptureLink) -> io::Result<Self> {
+        Self::new(inner, BufWriter::new(File::create(path)?), link)
+    }
+
+    /// Unwrap the inner transport
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: SmpTransport> SmpTransport for PcapngTransport<T> {
+    fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
+        self.writer.write_frame(Direction::Outbound, &frame)?;
+        self.inner.send(frame)
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        let frame = self.inner.receive()?;
+        self.writer.write_frame(Direction::Inbound, &frame)?;
+        Ok(frame)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<T: crate::transport::smp::SmpTransportAsync + Send> crate::transport::smp::SmpTransportAsync
+    for PcapngTransport<T>
+{
+    async fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
+        self.writer.write_frame(Direction::Outbound, &frame)?;
+        self.inner.send(frame).await
+    }
+
+    async fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        let frame = self.inner.receive().await?;
+        self.writer.write_frame(Direction::Inbound, &frame)?;
+        Ok(frame)
+    }
+}
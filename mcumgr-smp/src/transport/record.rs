@@ -0,0 +1,317 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::transport::error::Error;
+use crate::transport::smp::SmpTransport;
+use crate::SmpHeader;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// What happened on the transport
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// a frame was sent to the device
+    Sent(Vec<u8>),
+    /// a frame was received from the device
+    Received(Vec<u8>),
+    /// receiving timed out with [Error::Timeout]
+    Timeout,
+    /// receiving failed with an I/O or other error
+    Error(io::ErrorKind, String),
+}
+
+/// An event of a recording, `time` is relative to the start of the recording
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub time: Duration,
+    pub event: Event,
+}
+
+impl Record {
+    /// Format as a line of the recording file:
+    /// `<seconds> <tx|rx> <hex frame>`, `<seconds> timeout` or `<seconds> err <kind> <message>`
+    fn write_line(&self, mut writer: impl Write) -> io::Result<()> {
+        write!(
+            writer,
+            "{}.{:06} ",
+            self.time.as_secs(),
+            self.time.subsec_micros()
+        )?;
+        match &self.event {
+            Event::Sent(frame) => writeln!(writer, "tx {}", hex(frame)),
+            Event::Received(frame) => writeln!(writer, "rx {}", hex(frame)),
+            Event::Timeout => writeln!(writer, "timeout"),
+            Event::Error(kind, msg) => {
+                writeln!(writer, "err {:?} {}", kind, msg.replace('\n', " "))
+            }
+        }
+    }
+
+    fn parse_line(line: &str) -> Option<Self> {
+        let mut parts = line.splitn(3, ' ');
+        let time = parts.next()?.parse::<f64>().ok()?;
+        let time = Duration::try_from_secs_f64(time).ok()?;
+        let direction = parts.next()?;
+        let rest = parts.next().unwrap_or_default();
+
+        let event = match direction {
+            "tx" => Event::Sent(unhex(rest)?),
+            "rx" => Event::Received(unhex(rest)?),
+            "timeout" => Event::Timeout,
+            "err" => {
+                let (kind, msg) = rest.split_once(' ').unwrap_or((rest, ""));
+                Event::Error(error_kind(kind), msg.to_string())
+            }
+            _ => return None,
+        };
+        Some(Self { time, event })
+    }
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn unhex(s: &str) -> Option<Vec<u8>> {
+    s.trim()
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [_, _] => u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The error kinds a transport reports while receiving, everything else becomes `Other`
+fn error_kind(kind: &str) -> io::ErrorKind {
+    match kind {
+        "TimedOut" => io::ErrorKind::TimedOut,
+        "WouldBlock" => io::ErrorKind::WouldBlock,
+        "UnexpectedEof" => io::ErrorKind::UnexpectedEof,
+        "ConnectionReset" => io::ErrorKind::ConnectionReset,
+        "BrokenPipe" => io::ErrorKind::BrokenPipe,
+        "InvalidData" => io::ErrorKind::InvalidData,
+        _ => io::ErrorKind::Other,
+    }
+}
+
+/// Read a recording written by [RecordingTransport]
+pub fn read_recording(reader: impl BufRead) -> io::Result<Vec<Record>> {
+    let mut records = Vec::new();
+    for (n, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let record = Record::parse_line(line).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid recording in line {}", n + 1),
+            )
+        })?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Wraps a transport and writes every sent and received frame to a recording,
+/// to be replayed later with [ReplayTransport].
+///
+/// The recording is a text file with one event per line, see [Record].
+/// Works with sync and async transports.
+pub struct RecordingTransport<T> {
+    inner: T,
+    writer: Box<dyn Write + Send>,
+    start: Instant,
+}
+
+impl<T> RecordingTransport<T> {
+    /// Record to `writer`
+    pub fn new(inner: T, writer: impl Write + Send + 'static) -> io::Result<Self> {
+        let mut writer: Box<dyn Write + Send> = Box::new(writer);
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        writeln!(
+            writer,
+            "# SMP recording, started at {} (unix time)",
+            started.as_secs()
+        )?;
+        writer.flush()?;
+
+        Ok(Self {
+            inner,
+            writer,
+            start: Instant::now(),
+        })
+    }
+
+    /// Record to a newly created file at `path`
+    pub fn create(inner: T, path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::new(inner, BufWriter::new(File::create(path)?))
+    }
+
+    /// Unwrap the inner transport
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn record(&mut self, event: Event) -> Result<(), Error> {
+        let record = Record {
+            time: self.start.elapsed(),
+            event,
+        };
+        record.write_line(&mut self.writer)?;
+        // keep the recording complete if the program is killed
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn record_received(&mut self, frame: Result<Vec<u8>, Error>) -> Result<Vec<u8>, Error> {
+        match frame {
+            Ok(frame) => {
+                self.record(Event::Received(frame.clone()))?;
+                Ok(frame)
+            }
+            Err(Error::Timeout) => {
+                self.record(Event::Timeout)?;
+                Err(Error::Timeout)
+            }
+            Err(err) => {
+                let kind = match &err {
+                    Error::Io(err) => err.kind(),
+                    _ => io::ErrorKind::Other,
+                };
+                self.record(Event::Error(kind, err.to_string()))?;
+                Err(err)
+            }
+        }
+    }
+}
+
+impl<T: SmpTransport> SmpTransport for RecordingTransport<T> {
+    fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
+        self.record(Event::Sent(frame.clone()))?;
+        self.inner.send(frame)
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        let frame = self.inner.receive();
+        self.record_received(frame)
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl<T: crate::transport::smp::SmpTransportAsync + Send> crate::transport::smp::SmpTransportAsync
+    for RecordingTransport<T>
+{
+    async fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
+        self.record(Event::Sent(frame.clone()))?;
+        self.inner.send(frame).await
+    }
+
+    async fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        let frame = self.inner.receive().await;
+        self.record_received(frame)
+    }
+}
+
+/// Serves the responses of a recording made with [RecordingTransport].
+///
+/// Each receive returns the next frame or error recorded after the last sent frame.
+/// The sent frames aren't compared to the recording, so changed client code can be
+/// run against it, but the client has to send as many frames as the recording contains.
+/// Sequence numbers of the responses are adjusted to the frames actually sent.
+pub struct ReplayTransport {
+    records: Vec<Record>,
+    pos: usize,
+    /// sequence numbers of the last recorded and actually sent frame
+    sequence: Option<(u8, u8)>,
+}
+
+impl ReplayTransport {
+    pub fn new(records: Vec<Record>) -> Self {
+        Self {
+            records,
+            pos: 0,
+            sequence: None,
+        }
+    }
+
+    /// Replay the recording file at `path`
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = BufReader::new(File::open(path)?);
+        Ok(Self::new(read_recording(file)?))
+    }
+
+    fn handle_send(&mut self, frame: &[u8]) -> Result<(), Error> {
+        let recorded = loop {
+            let record = self
+                .records
+                .get(self.pos)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "end of recording"))?;
+            self.pos += 1;
+            // responses the client didn't wait for are skipped
+            if let Event::Sent(recorded) = &record.event {
+                break recorded;
+            }
+        };
+
+        let sequence = |frame: &[u8]| SmpHeader::parse(frame).map(|h| h.sequence()).ok();
+        self.sequence = sequence(recorded).zip(sequence(frame));
+        Ok(())
+    }
+
+    fn handle_receive(&mut self) -> Result<Vec<u8>, Error> {
+        let Some(record) = self.records.get(self.pos) else {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "end of recording").into());
+        };
+
+        match &record.event {
+            // the device didn't answer before the next request
+            Event::Sent(_) => Err(Error::Timeout),
+            Event::Received(frame) => {
+                self.pos += 1;
+                let mut frame = frame.clone();
+                if let (Some((recorded, sent)), Some(seq)) = (self.sequence, frame.get_mut(6)) {
+                    *seq = seq.wrapping_sub(recorded).wrapping_add(sent);
+                }
+                Ok(frame)
+            }
+            Event::Timeout => {
+                self.pos += 1;
+                Err(Error::Timeout)
+            }
+            Event::Error(kind, msg) => {
+                self.pos += 1;
+                Err(io::Error::new(*kind, msg.clone()).into())
+            }
+        }
+    }
+}
+
+impl SmpTransport for ReplayTransport {
+    fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
+        self.handle_send(&frame)
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        self.handle_receive()
+    }
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl crate::transport::smp::SmpTransportAsync for ReplayTransport {
+    async fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
+        self.handle_send(&frame)
+    }
+
+    async fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        self.handle_receive()
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::transport::console::{ConsoleTransportAsync, Pacing};
+use crate::transport::error::Error;
+use crate::transport::smp::SmpTransportAsync;
+use async_trait::async_trait;
+use std::io;
+use std::time::Duration;
+use tokio_serial::{SerialPortBuilderExt, SerialStream};
+
+/// Serial transport using the SMP console framing, on tokio.
+///
+/// Receiving is cancel safe, see [ConsoleTransportAsync].
+pub struct SerialTransportAsync {
+    transport: ConsoleTransportAsync<SerialStream>,
+}
+
+impl SerialTransportAsync {
+    pub fn new(port: String, baud_rate: u32) -> Result<Self, Error> {
+        let serial = tokio_serial::new(port, baud_rate)
+            .open_native_async()
+            .map_err(io::Error::from)?;
+        Ok(Self::from_stream(serial))
+    }
+
+    /// Use an already opened serial port, e.g. one end of `SerialStream::pair()`
+    pub fn from_stream(serial: SerialStream) -> Self {
+        Self {
+            transport: ConsoleTransportAsync::new(serial),
+        }
+    }
+
+    /// Fail a receive with [Error::Timeout] if no complete frame arrives in time.
+    /// `None` waits forever.
+    pub fn recv_timeout(&mut self, timeout: Option<Duration>) {
+        self.transport.recv_timeout(timeout);
+    }
+
+    /// Drop a partially received frame
+    pub fn reset(&mut self) {
+        self.transport.reset();
+    }
+
+    /// Call `handler` with each line of console output received between frames, e.g. log messages
+    pub fn on_console_output(&mut self, handler: impl FnMut(&[u8]) + Send + 'static) {
+        self.transport.on_console_output(handler);
+    }
+
+    /// Call `handler` with the error of each skipped invalid frame, e.g. one with a wrong CRC
+    pub fn on_invalid_frame(&mut self, handler: impl FnMut(Error) + Send + 'static) {
+        self.transport.on_invalid_frame(handler);
+    }
+
+    /// Pace sending for devices with small UART receive buffers, see [Pacing]
+    pub fn set_pacing(&mut self, pacing: Pacing) {
+        self.transport.set_pacing(pacing);
+    }
+}
+
+#[async_trait]
+impl SmpTransportAsync for SerialTransportAsync {
+    async fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
+        self.transport.send(frame).await
+    }
+
+    async fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        self.transport.receive().await
+    }
+}
@@ -0,0 +1,55 @@
+// Author: Sascha Zenglein <zenglein@gessler.de>
+// Copyright (c) 2023 Gessler GmbH.
+
+use crate::transport::console::{ConsoleTransport, Pacing};
+use crate::transport::error::Error;
+use crate::transport::smp::SmpTransport;
+use serialport::SerialPort;
+use std::time::Duration;
+
+pub struct SerialTransport {
+    transport: ConsoleTransport<Box<dyn SerialPort>>,
+}
+
+impl SerialTransport {
+    pub fn new(port: String, baud_rate: u32) -> Result<Self, Box<dyn std::error::Error>> {
+        let serial = serialport::new(port, baud_rate).open_native()?;
+        Ok(Self {
+            transport: ConsoleTransport::new(Box::new(serial)),
+        })
+    }
+
+    /// Fail a receive with [Error::Timeout] if the port doesn't receive data in time.
+    /// `None` waits forever.
+    pub fn recv_timeout(&mut self, timeout: Option<Duration>) -> Result<(), Error> {
+        self.transport
+            .get_mut()
+            .set_timeout(timeout.unwrap_or(Duration::MAX))
+            .map_err(|e| Error::Io(e.into()))
+    }
+
+    /// Call `handler` with each line of console output received between frames, e.g. log messages
+    pub fn on_console_output(&mut self, handler: impl FnMut(&[u8]) + Send + 'static) {
+        self.transport.on_console_output(handler);
+    }
+
+    /// Call `handler` with the error of each skipped invalid frame, e.g. one with a wrong CRC
+    pub fn on_invalid_frame(&mut self, handler: impl FnMut(Error) + Send + 'static) {
+        self.transport.on_invalid_frame(handler);
+    }
+
+    /// Pace sending for devices with small UART receive buffers, see [Pacing]
+    pub fn set_pacing(&mut self, pacing: Pacing) {
+        self.transport.set_pacing(pacing);
+    }
+}
+
+impl SmpTransport for SerialTransport {
+    fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
+        self.transport.send(frame)
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        self.transport.receive()
+    }
+}
@@ -9,3 +9,6 @@ pub mod smp_sync;
 #[cfg(feature = "payload-cbor")]
 pub use smp_sync::cbor::CborSmpTransport;
 pub use smp_sync::SmpTransport;
+
+#[cfg(feature = "payload-cbor")]
+mod sequence;
@@ -0,0 +1,82 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::transport::error::Error;
+use crate::{OpCode, SmpHeader};
+
+/// Assigns sequence numbers to outgoing requests and matches responses to them.
+///
+/// Responses to other outstanding requests are kept until they are asked for,
+/// responses to requests that are no longer outstanding (e.g. late replies to a
+/// request that timed out) are dropped.
+#[derive(Default)]
+pub(crate) struct Sequencer {
+    next: u8,
+    outstanding: Vec<u8>,
+    received: Vec<Vec<u8>>,
+}
+
+impl Sequencer {
+    /// Allocate the sequence number for a new request
+    pub fn next(&mut self) -> u8 {
+        let sequence = self.next;
+        self.next = self.next.wrapping_add(1);
+
+        // the counter wrapped around, an old request with this number won't be answered anymore
+        self.cancel(sequence);
+        self.outstanding.push(sequence);
+
+        sequence
+    }
+
+    /// The sequence number of the most recent request
+    pub fn last(&self) -> u8 {
+        self.next.wrapping_sub(1)
+    }
+
+    /// Stop waiting for the response to a request
+    pub fn cancel(&mut self, sequence: u8) {
+        self.outstanding.retain(|&s| s != sequence);
+        self.received
+            .retain(|frame| SmpHeader::parse(frame).map(|h| h.sequence()).ok() != Some(sequence));
+    }
+
+    /// Whether a response to the request is still awaited
+    pub fn is_outstanding(&self, sequence: u8) -> bool {
+        self.outstanding.contains(&sequence)
+    }
+
+    /// Take an already received response to the request
+    pub fn take_received(&mut self, sequence: u8) -> Option<Vec<u8>> {
+        let pos = self.received.iter().position(|frame| {
+            SmpHeader::parse(frame).map(|h| h.sequence()).ok() == Some(sequence)
+        })?;
+        self.outstanding.retain(|&s| s != sequence);
+        Some(self.received.remove(pos))
+    }
+
+    /// Sort a received frame.  
+    /// Returns the frame if it answers `sequence`, otherwise it is kept for another
+    /// outstanding request or dropped.
+    pub fn accept(&mut self, sequence: u8, frame: Vec<u8>) -> Result<Option<Vec<u8>>, Error> {
+        let header = SmpHeader::parse(&frame)?;
+        let is_response = matches!(
+            header.operation(),
+            OpCode::ReadResponse | OpCode::WriteResponse
+        );
+        if !is_response || !self.outstanding.contains(&header.sequence()) {
+            return Ok(None);
+        }
+
+        if header.sequence() == sequence {
+            self.outstanding.retain(|&s| s != sequence);
+            Ok(Some(frame))
+        } else {
+            // a duplicate replaces the earlier copy
+            let other = header.sequence();
+            self.received
+                .retain(|f| SmpHeader::parse(f).map(|h| h.sequence()).ok() != Some(other));
+            self.received.push(frame);
+            Ok(None)
+        }
+    }
+}
@@ -12,15 +12,51 @@ pub trait SmpTransportAsync {
 
 #[cfg(feature = "payload-cbor")]
 pub mod cbor {
+    use crate::codec::{CborCodec, DecodePayload, EncodePayload, PayloadCodec};
+    use crate::response::SmpResponse;
     use crate::transport::error::Error;
+    use crate::transport::smp::sequence::Sequencer;
     use crate::transport::smp::SmpTransportAsync;
-    use crate::SmpFrame;
+    use crate::{SmpError, SmpFrame, SmpHeader, SmpRequest, SmpVersion};
+    use std::marker::PhantomData;
 
-    pub struct CborSmpTransportAsync {
+    /// Encodes and decodes frames sent over a [SmpTransportAsync].  
+    /// Payloads are encoded with CBOR by default, other formats can be used
+    /// by selecting a different [PayloadCodec].
+    ///
+    /// Sequence numbers are assigned from an internal counter and responses are
+    /// matched to their request by sequence number.
+    pub struct CborSmpTransportAsync<C = CborCodec> {
         pub transport: Box<dyn SmpTransportAsync>,
+        version: SmpVersion,
+        sequencer: Sequencer,
+        codec: PhantomData<C>,
     }
 
     impl CborSmpTransportAsync {
+        /// Wrap a transport. Frames are sent as SMP version 2 until the device
+        /// answers with a version 1 header, after which version 1 is used.
+        pub fn new(transport: Box<dyn SmpTransportAsync>) -> Self {
+            Self::with_codec(transport)
+        }
+    }
+
+    impl<C: PayloadCodec> CborSmpTransportAsync<C> {
+        /// Wrap a transport, encoding payloads with the codec `C`
+        pub fn with_codec(transport: Box<dyn SmpTransportAsync>) -> Self {
+            Self {
+                transport,
+                version: SmpVersion::V2,
+                sequencer: Sequencer::default(),
+                codec: PhantomData,
+            }
+        }
+
+        /// The SMP version used for outgoing frames
+        pub fn version(&self) -> SmpVersion {
+            self.version
+        }
+
         pub async fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
             self.transport.send(frame).await
         }
@@ -33,35 +69,114 @@ pub mod cbor {
             self.transport.receive().await
         }
 
-        pub async fn send_cbor<T: serde::Serialize>(
+        /// Send a frame with the next sequence number and return that number.  
+        /// The sequence number of `frame` is ignored.
+        pub async fn send_frame<T>(&mut self, frame: &SmpFrame<T>) -> Result<u8, Error>
+        where
+            C: EncodePayload<T>,
+        {
+            let sequence = self.sequencer.next();
+            let bytes = frame.encode_as::<C>(frame.version.min(self.version), sequence)?;
+            if let Err(err) = self.send(bytes).await {
+                self.sequencer.cancel(sequence);
+                return Err(err);
+            }
+            Ok(sequence)
+        }
+
+        /// Receive the response to the frame sent with `sequence`.  
+        /// Responses to earlier requests that are no longer awaited are dropped,
+        /// responses to other outstanding requests are kept until they are received.
+        /// Fails with [SmpError::UnexpectedSeq] if no response to `sequence` is awaited.
+        pub async fn receive_frame<T>(&mut self, sequence: u8) -> Result<SmpFrame<T>, Error>
+        where
+            C: DecodePayload<T>,
+        {
+            let bytes = match self.sequencer.take_received(sequence) {
+                Some(bytes) => bytes,
+                // no request is waiting for this response, it would never be received
+                None if !self.sequencer.is_outstanding(sequence) => {
+                    return Err(Error::Smp(SmpError::UnexpectedSeq));
+                }
+                None => loop {
+                    let bytes = self.receive().await?;
+                    if let Some(bytes) = self.sequencer.accept(sequence, bytes)? {
+                        break bytes;
+                    }
+                },
+            };
+
+            let header = SmpHeader::parse(&bytes)?;
+            // fall back to the legacy protocol if the device does not speak v2
+            if header.version() < self.version {
+                self.version = header.version();
+            }
+            Ok(SmpFrame::<T>::decode::<C>(&bytes)?)
+        }
+
+        pub async fn transceive_frame<Req, Resp>(
             &mut self,
-            frame: &SmpFrame<T>,
-        ) -> Result<(), Error> {
-            let bytes = frame.encode_with_cbor();
-            self.send(bytes).await
+            frame: &SmpFrame<Req>,
+        ) -> Result<SmpFrame<Resp>, Error>
+        where
+            C: EncodePayload<Req> + DecodePayload<Resp>,
+        {
+            let sequence = self.send_frame(frame).await?;
+            let response = self.receive_frame(sequence).await;
+            if response.is_err() {
+                // don't mistake a late reply for the response to a later request
+                self.sequencer.cancel(sequence);
+            }
+            response
+        }
+
+        /// Send a frame with the next sequence number and return that number
+        #[deprecated(note = "use `send_frame`")]
+        pub async fn send_cbor<T>(&mut self, frame: &SmpFrame<T>) -> Result<u8, Error>
+        where
+            C: EncodePayload<T>,
+        {
+            self.send_frame(frame).await
         }
-        pub async fn receive_cbor<T: serde::de::DeserializeOwned>(
+
+        /// Receive the response to the frame sent with `expected_sequence`,
+        /// or to the last sent frame if it is `None`
+        #[deprecated(note = "use `receive_frame`")]
+        pub async fn receive_cbor<T>(
             &mut self,
             expected_sequence: Option<u8>,
-        ) -> Result<SmpFrame<T>, Error> {
-            let bytes = self.receive().await?;
-            let frame = SmpFrame::<T>::decode_with_cbor(&bytes)?;
-            if let Some(expected_sequence) = expected_sequence {
-                if frame.sequence != expected_sequence {
-                    Err(Error::Smp(crate::SmpError::UnexpectedSeq))?;
-                }
-            }
-            Ok(frame)
+        ) -> Result<SmpFrame<T>, Error>
+        where
+            C: DecodePayload<T>,
+        {
+            let sequence = expected_sequence.unwrap_or(self.sequencer.last());
+            self.receive_frame(sequence).await
         }
 
-        pub async fn transceive_cbor<Req: serde::Serialize, Resp: serde::de::DeserializeOwned>(
+        /// Send a frame and receive its response.  
+        /// Sequence numbers are always checked, `check_sequence` is ignored.
+        #[deprecated(note = "use `transceive_frame`")]
+        pub async fn transceive_cbor<Req, Resp>(
             &mut self,
             frame: &SmpFrame<Req>,
-            check_sequence: bool,
-        ) -> Result<SmpFrame<Resp>, Error> {
-            self.send_cbor(frame).await?;
-            self.receive_cbor(check_sequence.then_some(frame.sequence))
-                .await
+            _check_sequence: bool,
+        ) -> Result<SmpFrame<Resp>, Error>
+        where
+            C: EncodePayload<Req> + DecodePayload<Resp>,
+        {
+            self.transceive_frame(frame).await
+        }
+
+        /// Send a request and decode its response.  
+        /// Errors reported by the device are returned as [Error::Device].
+        pub async fn request<R: SmpRequest>(&mut self, request: R) -> Result<R::Response, Error>
+        where
+            C: EncodePayload<R> + DecodePayload<SmpResponse<R::Response>>,
+        {
+            let frame = request.into_frame(0);
+            let response: SmpFrame<SmpResponse<R::Response>> =
+                self.transceive_frame(&frame).await?;
+            Ok(response.data.into_result()?)
         }
     }
 }
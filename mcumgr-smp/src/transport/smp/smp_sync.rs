@@ -10,15 +10,51 @@ pub trait SmpTransport {
 
 #[cfg(feature = "payload-cbor")]
 pub mod cbor {
-    use crate::smp::SmpFrame;
+    use crate::codec::{CborCodec, DecodePayload, EncodePayload, PayloadCodec};
+    use crate::response::SmpResponse;
+    use crate::smp::{SmpError, SmpFrame, SmpHeader, SmpRequest, SmpVersion};
     use crate::transport::error::Error;
+    use crate::transport::smp::sequence::Sequencer;
     use crate::transport::smp::SmpTransport;
+    use std::marker::PhantomData;
 
-    pub struct CborSmpTransport {
+    /// Encodes and decodes frames sent over a [SmpTransport].  
+    /// Payloads are encoded with CBOR by default, other formats can be used
+    /// by selecting a different [PayloadCodec].
+    ///
+    /// Sequence numbers are assigned from an internal counter and responses are
+    /// matched to their request by sequence number.
+    pub struct CborSmpTransport<C = CborCodec> {
         pub transport: Box<dyn SmpTransport>,
+        version: SmpVersion,
+        sequencer: Sequencer,
+        codec: PhantomData<C>,
     }
 
     impl CborSmpTransport {
+        /// Wrap a transport. Frames are sent as SMP version 2 until the device
+        /// answers with a version 1 header, after which version 1 is used.
+        pub fn new(transport: Box<dyn SmpTransport>) -> Self {
+            Self::with_codec(transport)
+        }
+    }
+
+    impl<C: PayloadCodec> CborSmpTransport<C> {
+        /// Wrap a transport, encoding payloads with the codec `C`
+        pub fn with_codec(transport: Box<dyn SmpTransport>) -> Self {
+            Self {
+                transport,
+                version: SmpVersion::V2,
+                sequencer: Sequencer::default(),
+                codec: PhantomData,
+            }
+        }
+
+        /// The SMP version used for outgoing frames
+        pub fn version(&self) -> SmpVersion {
+            self.version
+        }
+
         pub fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
             self.transport.send(frame)
         }
@@ -31,31 +67,113 @@ pub mod cbor {
             self.transport.receive()
         }
 
-        pub fn send_cbor<T: serde::Serialize>(&mut self, frame: &SmpFrame<T>) -> Result<(), Error> {
-            let bytes = frame.encode_with_cbor();
-            self.send(bytes)
+        /// Send a frame with the next sequence number and return that number.  
+        /// The sequence number of `frame` is ignored.
+        pub fn send_frame<T>(&mut self, frame: &SmpFrame<T>) -> Result<u8, Error>
+        where
+            C: EncodePayload<T>,
+        {
+            let sequence = self.sequencer.next();
+            let bytes = frame.encode_as::<C>(frame.version.min(self.version), sequence)?;
+            if let Err(err) = self.send(bytes) {
+                self.sequencer.cancel(sequence);
+                return Err(err);
+            }
+            Ok(sequence)
         }
-        pub fn receive_cbor<T: serde::de::DeserializeOwned>(
-            &mut self,
-            expected_sequence: Option<u8>,
-        ) -> Result<SmpFrame<T>, Error> {
-            let bytes = self.receive()?;
-            let frame = SmpFrame::<T>::decode_with_cbor(&bytes)?;
-            if let Some(expected_sequence) = expected_sequence {
-                if frame.sequence != expected_sequence {
-                    Err(Error::Smp(crate::SmpError::UnexpectedSeq))?;
+
+        /// Receive the response to the frame sent with `sequence`.  
+        /// Responses to earlier requests that are no longer awaited are dropped,
+        /// responses to other outstanding requests are kept until they are received.
+        /// Fails with [SmpError::UnexpectedSeq] if no response to `sequence` is awaited.
+        pub fn receive_frame<T>(&mut self, sequence: u8) -> Result<SmpFrame<T>, Error>
+        where
+            C: DecodePayload<T>,
+        {
+            let bytes = match self.sequencer.take_received(sequence) {
+                Some(bytes) => bytes,
+                // no request is waiting for this response, it would never be received
+                None if !self.sequencer.is_outstanding(sequence) => {
+                    return Err(Error::Smp(SmpError::UnexpectedSeq));
                 }
+                None => loop {
+                    let bytes = self.receive()?;
+                    if let Some(bytes) = self.sequencer.accept(sequence, bytes)? {
+                        break bytes;
+                    }
+                },
+            };
+
+            let header = SmpHeader::parse(&bytes)?;
+            // fall back to the legacy protocol if the device does not speak v2
+            if header.version() < self.version {
+                self.version = header.version();
             }
-            Ok(frame)
+            Ok(SmpFrame::<T>::decode::<C>(&bytes)?)
         }
 
-        pub fn transceive_cbor<Req: serde::Serialize, Resp: serde::de::DeserializeOwned>(
+        pub fn transceive_frame<Req, Resp>(
             &mut self,
             frame: &SmpFrame<Req>,
-            check_sequence: bool,
-        ) -> Result<SmpFrame<Resp>, Error> {
-            self.send_cbor(frame)?;
-            self.receive_cbor(check_sequence.then_some(frame.sequence))
+        ) -> Result<SmpFrame<Resp>, Error>
+        where
+            C: EncodePayload<Req> + DecodePayload<Resp>,
+        {
+            let sequence = self.send_frame(frame)?;
+            let response = self.receive_frame(sequence);
+            if response.is_err() {
+                // don't mistake a late reply for the response to a later request
+                self.sequencer.cancel(sequence);
+            }
+            response
+        }
+
+        /// Send a frame with the next sequence number and return that number
+        #[deprecated(note = "use `send_frame`")]
+        pub fn send_cbor<T>(&mut self, frame: &SmpFrame<T>) -> Result<u8, Error>
+        where
+            C: EncodePayload<T>,
+        {
+            self.send_frame(frame)
+        }
+
+        /// Receive the response to the frame sent with `expected_sequence`,
+        /// or to the last sent frame if it is `None`
+        #[deprecated(note = "use `receive_frame`")]
+        pub fn receive_cbor<T>(
+            &mut self,
+            expected_sequence: Option<u8>,
+        ) -> Result<SmpFrame<T>, Error>
+        where
+            C: DecodePayload<T>,
+        {
+            let sequence = expected_sequence.unwrap_or(self.sequencer.last());
+            self.receive_frame(sequence)
+        }
+
+        /// Send a frame and receive its response.  
+        /// Sequence numbers are always checked, `check_sequence` is ignored.
+        #[deprecated(note = "use `transceive_frame`")]
+        pub fn transceive_cbor<Req, Resp>(
+            &mut self,
+            frame: &SmpFrame<Req>,
+            _check_sequence: bool,
+        ) -> Result<SmpFrame<Resp>, Error>
+        where
+            C: EncodePayload<Req> + DecodePayload<Resp>,
+        {
+            self.transceive_frame(frame)
+        }
+
+        /// Send a request and decode its response.  
+        /// Errors reported by the device are returned as [Error::Device].
+        pub fn request<R: SmpRequest>(&mut self, request: R) -> Result<R::Response, Error>
+        where
+            C: EncodePayload<R> + DecodePayload<SmpResponse<R::Response>>,
+        {
+            let frame = request.into_frame(0);
+            let response: SmpFrame<SmpResponse<R::Response>> = self.transceive_frame(&frame)?;
+            Ok(response.data.into_result()?)
         }
     }
 }
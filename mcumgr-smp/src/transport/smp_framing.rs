@@ -1,10 +1,11 @@
 // Author: Sascha Zenglein <zenglein@gessler.de>
 // Copyright (c) 2023 Gessler GmbH.
 
+use alloc::vec::Vec;
 use base64::engine::general_purpose;
 use base64::{EncodeSliceError, Engine};
+use core::cmp::min;
 use crc::Crc;
-use std::cmp::min;
 
 /// there are multiple possible CRC implementations. This matches the results from mcumgr
 const CALC_CRC: Crc<u16> = crc::Crc::<u16>::new(&crc::CRC_16_XMODEM);
@@ -19,14 +20,60 @@ pub enum SmpTransportError {
     PacketLength(u16, usize),
     #[error("wrong crc")]
     CRCError,
+    #[error("line too short: {0} bytes")]
+    LineTooShort(usize),
+    #[error("line longer than {0} bytes")]
+    LineTooLong(usize),
+    #[error("initial packet is missing the length field")]
+    MissingLength,
+    #[error("payload too large: {0} bytes")]
+    PayloadTooLarge(usize),
+    #[error("output buffer too small")]
+    BufferTooSmall,
     #[error("base64 decoding error: {0}")]
-    Base64DecodeError(#[from] base64::DecodeError),
+    // base64 only implements `Error` with std
+    Base64DecodeError(#[cfg_attr(feature = "std", source)] base64::DecodeError),
 }
 
+impl From<base64::DecodeError> for SmpTransportError {
+    fn from(err: base64::DecodeError) -> Self {
+        SmpTransportError::Base64DecodeError(err)
+    }
+}
+
+impl From<EncodeSliceError> for SmpTransportError {
+    fn from(_: EncodeSliceError) -> Self {
+        SmpTransportError::BufferTooSmall
+    }
+}
+
+/// Start of the first line of a frame
+const FRAME_START: [u8; 2] = [0x06, 0x09];
+/// Start of the following lines of a frame
+const FRAME_CONTINUATION: [u8; 2] = [0x04, 0x14];
+
+/// Decoded console input, see [SmpTransportDecoder::next_input]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConsoleInput {
+    /// a complete SMP frame
+    Frame(Vec<u8>),
+    /// a line of other console output, e.g. log messages, without line ending
+    Text(Vec<u8>),
+}
+
+/// Decodes frames from the SMP console framing.
+///
+/// Lines can be passed one by one to [input_line](Self::input_line), or the received data
+/// to [push](Self::push) and [next_input](Self::next_input), which also handle partial lines
+/// and separate other console output, e.g. log messages, from the frames.
 pub struct SmpTransportDecoder {
     /// length + 2 bytes CRC
     content_length: u16,
     buf: Vec<u8>,
+    /// received data not yet split into lines
+    pending: Vec<u8>,
+    /// the rest of a line longer than [MAX_LINE_LENGTH] is dropped
+    discard_line: bool,
 }
 
 impl Default for SmpTransportDecoder {
@@ -40,23 +87,47 @@ impl SmpTransportDecoder {
         Self {
             content_length: 0,
             buf: Vec::with_capacity(127),
+            pending: Vec::new(),
+            discard_line: false,
         }
     }
 
-    /// attempt to parse a packet from the input buffer and return whether the frame is complete
+    /// attempt to parse a packet from the input buffer and return whether the frame is complete.
+    ///
+    /// The line may end with `\n` or `\r\n`. Lines that don't start a frame or continue one
+    /// fail with [SmpTransportError::UnknownFrameStart] and leave the decoder unchanged,
+    /// so they can be skipped. A line starting a new frame drops a partially received one,
+    /// e.g. after the device was reset in the middle of a response.
     pub fn input_line(&mut self, input: &[u8]) -> Result<bool, SmpTransportError> {
-        let start = (input[0], input[1]);
-        let base64_packet = general_purpose::STANDARD.decode(&input[2..input.len() - 1])?;
+        let (start, body) = match input {
+            [a, b, body @ ..] => ((*a, *b), body),
+            _ => return Err(SmpTransportError::LineTooShort(input.len())),
+        };
+        if ![FRAME_START, FRAME_CONTINUATION].contains(&[start.0, start.1]) {
+            return Err(SmpTransportError::UnknownFrameStart([start.0, start.1]));
+        }
+        let body = trim_line_ending(body);
+        let base64_packet = general_purpose::STANDARD.decode(body)?;
 
         let packet_body = match start {
             (0x06, 0x09) => {
-                if self.content_length > 0 {
-                    return Err(SmpTransportError::UnexpectedFrame);
+                self.reset();
+
+                let [len_hi, len_lo, packet_body @ ..] = base64_packet.as_slice() else {
+                    return Err(SmpTransportError::MissingLength);
+                };
+
+                // the length includes the 2 byte CRC
+                let content_length = u16::from_be_bytes([*len_hi, *len_lo]);
+                if content_length < 2 {
+                    return Err(SmpTransportError::PacketLength(
+                        content_length,
+                        packet_body.len(),
+                    ));
                 }
+                self.content_length = content_length;
 
-                self.content_length = u16::from_be_bytes([base64_packet[0], base64_packet[1]]);
-
-                &base64_packet[2..]
+                packet_body
             }
             (0x04, 0x14) => {
                 if self.content_length == 0 {
@@ -84,15 +155,105 @@ impl SmpTransportDecoder {
         self.content_length != 0 && self.buf.len() >= self.content_length as usize
     }
 
-    pub fn into_frame_payload(self) -> Result<Vec<u8>, SmpTransportError> {
-        if self.buf.len() < 2 || self.buf.len() != self.content_length as usize {
-            return Err(SmpTransportError::PacketLength(
-                self.content_length,
-                self.buf.len(),
-            ));
+    pub fn into_frame_payload(mut self) -> Result<Vec<u8>, SmpTransportError> {
+        self.take_frame()
+    }
+
+    /// Append received data, which may end with a partial line.
+    /// Lines longer than [MAX_LINE_LENGTH] are dropped by [next_input](Self::next_input).
+    pub fn push(&mut self, data: &[u8]) {
+        self.pending.extend_from_slice(data);
+    }
+
+    /// Decode the next frame or line of console output from the data passed to [push](Self::push).  
+    /// Returns `None` if more data is needed.
+    ///
+    /// Console output may be interleaved with the lines of a frame. If a frame starts
+    /// in the middle of a line, the text before it is returned as [ConsoleInput::Text].
+    /// After an error, the partially decoded frame is dropped and decoding continues
+    /// with the next line.
+    ///
+    /// A line longer than [MAX_LINE_LENGTH], e.g. binary data without newlines, is dropped
+    /// with [SmpTransportError::LineTooLong] instead of buffering it without limit.
+    pub fn next_input(&mut self) -> Option<Result<ConsoleInput, SmpTransportError>> {
+        loop {
+            let end = self.pending.iter().position(|&b| b == b'\n');
+            if self.discard_line {
+                let Some(end) = end else {
+                    self.pending.clear();
+                    return None;
+                };
+                self.pending.drain(..=end);
+                self.discard_line = false;
+                continue;
+            }
+            if end.unwrap_or(self.pending.len()) >= MAX_LINE_LENGTH {
+                match end {
+                    Some(end) => {
+                        self.pending.drain(..=end);
+                    }
+                    None => {
+                        self.pending.clear();
+                        self.discard_line = true;
+                    }
+                }
+                self.reset();
+                return Some(Err(SmpTransportError::LineTooLong(MAX_LINE_LENGTH)));
+            }
+            let end = end?;
+            let frame_pos = self.pending[..end]
+                .windows(2)
+                .position(|start| start == FRAME_START || start == FRAME_CONTINUATION);
+
+            match frame_pos {
+                None if self.pending[..end].iter().all(|&b| b == b'\r') => {
+                    // empty line
+                    self.pending.drain(..=end);
+                }
+                None => {
+                    let line: Vec<u8> = self.pending.drain(..=end).collect();
+                    return Some(Ok(ConsoleInput::Text(trim_line_ending(&line).to_vec())));
+                }
+                Some(pos) if pos > 0 => {
+                    let text: Vec<u8> = self.pending.drain(..pos).collect();
+                    return Some(Ok(ConsoleInput::Text(text)));
+                }
+                Some(_) => {
+                    let line: Vec<u8> = self.pending.drain(..=end).collect();
+                    let res = self.input_line(&line).and_then(|complete| {
+                        Ok(if complete {
+                            Some(ConsoleInput::Frame(self.take_frame()?))
+                        } else {
+                            None
+                        })
+                    });
+                    match res {
+                        Ok(Some(input)) => return Some(Ok(input)),
+                        Ok(None) => {}
+                        Err(err) => {
+                            self.reset();
+                            return Some(Err(err));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Drop a partially decoded frame, keeping data passed to [push](Self::push) that wasn't decoded yet
+    pub fn reset(&mut self) {
+        self.content_length = 0;
+        self.buf.clear();
+    }
+
+    /// Check and take the complete frame, resetting the decoder for the next one
+    fn take_frame(&mut self) -> Result<Vec<u8>, SmpTransportError> {
+        let content_length = core::mem::take(&mut self.content_length);
+        let mut body = core::mem::take(&mut self.buf);
+        if body.len() < 2 || body.len() != content_length as usize {
+            return Err(SmpTransportError::PacketLength(content_length, body.len()));
         }
 
-        let mut body = self.buf;
         let crc = u16::from_be_bytes([body[body.len() - 2], body[body.len() - 1]]);
         body.truncate(body.len() - 2);
 
@@ -108,83 +269,118 @@ impl SmpTransportDecoder {
     }
 }
 
+/// Strip a trailing `\n` or `\r\n`
+fn trim_line_ending(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Max. line length of the console framing used by mcumgr and Zephyr, including the newline
+pub const DEFAULT_LINE_LENGTH: usize = 127;
+/// Shortest supported line, fits the length field, one payload byte and the CRC
+pub const MIN_LINE_LENGTH: usize = 11;
+/// Longest line kept by [SmpTransportDecoder::next_input], including the newline
+pub const MAX_LINE_LENGTH: usize = 4 * DEFAULT_LINE_LENGTH;
+
 pub struct SmpTransportEncoder<'a> {
+    /// the first line, with the length field, was written
+    started: bool,
     written_len: usize,
     payload: &'a [u8],
+    line_length: usize,
 }
 
 impl<'a> SmpTransportEncoder<'a> {
     pub fn new(payload: &'a [u8]) -> Self {
         Self {
+            started: false,
             written_len: 0,
             payload,
+            line_length: DEFAULT_LINE_LENGTH,
         }
     }
 
+    /// Limit lines to `line_length` bytes including the newline, instead of [DEFAULT_LINE_LENGTH].  
+    /// Values below [MIN_LINE_LENGTH] are raised to it.
+    pub fn with_line_length(mut self, line_length: usize) -> Self {
+        self.line_length = line_length.max(MIN_LINE_LENGTH);
+        self
+    }
+
+    pub fn line_length(&self) -> usize {
+        self.line_length
+    }
+
     /// Write the next line for the given payload to the supplied buffer.   
-    /// returns an error if out_buf is smaller than 127 bytes
-    pub fn write_line(&mut self, out_buf: &mut [u8]) -> Result<usize, EncodeSliceError> {
-        // max 127 with header and newline in base64 encoding
-        const MAX_RAW_BODY_LEN: usize = 93; // 124.0 / 4.0 * 3.0 as usize;
-        let mut base64_payload = Vec::with_capacity(MAX_RAW_BODY_LEN);
-
-        // println!(
-        //     "write_line: Written: {}; Total: {}",
-        //     self.written_len,
-        //     self.payload.len()
-        // );
-
-        if self.written_len == 0 {
-            out_buf[0] = 0x06;
-            out_buf[1] = 0x09;
-            base64_payload.extend_from_slice(&(self.payload.len() as u16 + 2).to_be_bytes());
+    /// returns an error if out_buf is smaller than the line length (127 bytes by default)
+    pub fn write_line(&mut self, out_buf: &mut [u8]) -> Result<usize, SmpTransportError> {
+        // base64 encoded body between the 2 byte start and the newline
+        let max_raw_body_len = (self.line_length - 3) / 4 * 3;
+
+        if out_buf.len() < self.line_length {
+            return Err(SmpTransportError::BufferTooSmall);
+        }
+        // the length field includes the 2 byte CRC
+        if self.payload.len() > u16::MAX as usize - 2 {
+            return Err(SmpTransportError::PayloadTooLarge(self.payload.len()));
+        }
+
+        let mut length_field = [0u8; 2];
+        let mut raw_len = 0;
+        if !self.started {
+            self.started = true;
+            out_buf[..2].copy_from_slice(&FRAME_START);
+            length_field = (self.payload.len() as u16 + 2).to_be_bytes();
+            raw_len = 2;
         } else {
-            out_buf[0] = 0x04;
-            out_buf[1] = 0x14;
+            out_buf[..2].copy_from_slice(&FRAME_CONTINUATION);
         }
+        let length_field = &length_field[..raw_len];
 
         let remaining_len = self.payload.len() - self.written_len;
-        let last_frame = remaining_len <= MAX_RAW_BODY_LEN - base64_payload.len() - 2;
+        let last_frame = remaining_len <= max_raw_body_len - raw_len - 2;
 
         let payload_len = if last_frame {
             remaining_len
         } else {
-            min(MAX_RAW_BODY_LEN - base64_payload.len(), remaining_len)
+            // keep at least one byte for the last line, so the CRC is not dropped
+            min(max_raw_body_len - raw_len, remaining_len - 1)
         };
-
-        base64_payload
-            .extend_from_slice(&self.payload[self.written_len..self.written_len + payload_len]);
+        let payload = &self.payload[self.written_len..self.written_len + payload_len];
         self.written_len += payload_len;
 
+        let mut crc = [0u8; 2];
         if last_frame {
             let mut digest = CALC_CRC.digest();
             digest.update(self.payload);
-            let crc_result = digest.finalize();
-            // println!(
-            //     "appending crc16 {:x?} to last frame",
-            //     &crc_result.to_be_bytes()
-            // );
-            base64_payload.extend_from_slice(&crc_result.to_be_bytes());
+            crc = digest.finalize().to_be_bytes();
+        }
+        let crc = if last_frame { &crc[..] } else { &[] };
+
+        // encode in groups of 3 bytes, so no buffer for the raw line is needed
+        let mut raw = length_field.iter().chain(payload).chain(crc).copied();
+        let mut pos = 2;
+        loop {
+            let mut group = [0u8; 3];
+            let mut group_len = 0;
+            for (byte, value) in group.iter_mut().zip(raw.by_ref()) {
+                *byte = value;
+                group_len += 1;
+            }
+            if group_len == 0 {
+                break;
+            }
+            pos += general_purpose::STANDARD
+                .encode_slice(&group[..group_len], &mut out_buf[pos..self.line_length - 1])?;
         }
 
-        // println!("base64 payload: {:x?}", base64_payload);
-
-        let base64_len =
-            general_purpose::STANDARD.encode_slice(base64_payload, &mut out_buf[2..])?;
-
-        // println!(
-        //     "resulting base64 string: {}",
-        //     String::from_utf8(Vec::from(&out_buf[2..2 + base64_len])).unwrap()
-        // );
-
-        assert!(base64_len <= 124);
-
-        out_buf[2 + base64_len] = 0x0a; // newline
+        out_buf[pos] = 0x0a; // newline
 
-        Ok(base64_len + 3)
+        Ok(pos + 1)
     }
 
     pub fn is_complete(&self) -> bool {
-        self.written_len >= self.payload.len()
+        // an empty payload still needs a line with the length and CRC
+        self.started && self.written_len >= self.payload.len()
     }
 }
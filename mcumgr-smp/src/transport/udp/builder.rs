@@ -0,0 +1,238 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use socket2::{Domain, Protocol, Socket, Type};
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// Size of the receive buffer if none is set, the MTU of most links
+pub const DEFAULT_RECV_BUFFER_SIZE: usize = 1500;
+
+/// Address family used to reach the device
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AddressFamily {
+    /// Each address the target resolves to in turn, until a socket can be connected to one
+    #[default]
+    Any,
+    V4,
+    V6,
+}
+
+/// Options for [UdpTransport](super::UdpTransport) and [UdpTransportAsync](super::UdpTransportAsync).
+///
+/// ```no_run
+/// use mcumgr_smp::transport::udp::{AddressFamily, UdpTransport};
+///
+/// let transport = UdpTransport::builder()
+///     .family(AddressFamily::V6)
+///     .scope_id(2)
+///     .recv_buffer_size(9000)
+///     .connect(("fe80::1", 1337))?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct UdpTransportBuilder {
+    bind: Option<SocketAddr>,
+    #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+    interface: Option<String>,
+    scope_id: Option<u32>,
+    only_v6: Option<bool>,
+    family: AddressFamily,
+    pub(super) recv_buffer_size: usize,
+}
+
+impl Default for UdpTransportBuilder {
+    fn default() -> Self {
+        Self {
+            bind: None,
+            #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+            interface: None,
+            scope_id: None,
+            only_v6: None,
+            family: AddressFamily::Any,
+            recv_buffer_size: DEFAULT_RECV_BUFFER_SIZE,
+        }
+    }
+}
+
+impl UdpTransportBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Local address to send from. Defaults to an ephemeral port on all addresses
+    /// of the target's family.
+    pub fn bind(mut self, addr: SocketAddr) -> Self {
+        self.bind = Some(addr);
+        self
+    }
+
+    /// Only send and receive on the network interface with this name, e.g. `eth0`
+    #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+    pub fn interface(mut self, name: impl Into<String>) -> Self {
+        self.interface = Some(name.into());
+        self
+    }
+
+    /// Interface index for link-local IPv6 targets without a scope id, e.g. `fe80::1`
+    pub fn scope_id(mut self, scope_id: u32) -> Self {
+        self.scope_id = Some(scope_id);
+        self
+    }
+
+    /// Set `IPV6_V6ONLY` on IPv6 sockets, instead of the system default.
+    ///
+    /// IPv4-mapped targets, e.g. `::ffff:192.0.2.1`, are reached over IPv4, or over a
+    /// dual-stack socket if the bind address is IPv6. That fails if `only_v6` is `true`.
+    pub fn only_v6(mut self, only_v6: bool) -> Self {
+        self.only_v6 = Some(only_v6);
+        self
+    }
+
+    /// Use only IPv4 or IPv6 addresses of the target
+    pub fn family(mut self, family: AddressFamily) -> Self {
+        self.family = family;
+        self
+    }
+
+    /// Largest datagram that can be received, larger responses fail with
+    /// [Error::Truncated](crate::transport::error::Error::Truncated).
+    /// Defaults to [DEFAULT_RECV_BUFFER_SIZE], connecting fails if it is zero.
+    pub fn recv_buffer_size(mut self, size: usize) -> Self {
+        self.recv_buffer_size = size;
+        self
+    }
+
+    /// Create a socket connected to the first of `targets` that works
+    fn socket(
+        &self,
+        targets: impl Iterator<Item = SocketAddr>,
+    ) -> Result<std::net::UdpSocket, io::Error> {
+        self.check()?;
+
+        let mut error = None;
+        for target in targets {
+            let connected = self.target(target).and_then(|target| {
+                let matches = match self.family {
+                    AddressFamily::Any => true,
+                    AddressFamily::V4 => target.is_ipv4(),
+                    AddressFamily::V6 => target.is_ipv6(),
+                };
+                if !matches {
+                    return Ok(None);
+                }
+                let socket = self.bound_socket(target)?;
+                socket.connect(&target.into())?;
+                Ok(Some(socket))
+            });
+            match connected {
+                Ok(Some(socket)) => return Ok(socket.into()),
+                Ok(None) => {}
+                Err(err) => error = Some(err),
+            }
+        }
+
+        Err(error.unwrap_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::AddrNotAvailable,
+                "no address of the selected family",
+            )
+        }))
+    }
+
+    /// Fail on invalid options
+    pub(super) fn check(&self) -> Result<(), io::Error> {
+        if self.recv_buffer_size == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "receive buffer size must not be zero",
+            ));
+        }
+        Ok(())
+    }
+
+    /// The address to send to for `target`, with the scope id applied
+    /// and IPv4-mapped addresses unmapped unless sent from a dual-stack socket
+    pub(super) fn target(&self, mut target: SocketAddr) -> Result<SocketAddr, io::Error> {
+        if let SocketAddr::V6(v6) = &mut target {
+            if let Some(v4) = v6.ip().to_ipv4_mapped() {
+                if !self.bind.is_some_and(|bind| bind.is_ipv6()) {
+                    return Ok(SocketAddr::new(v4.into(), v6.port()));
+                }
+                if self.only_v6 == Some(true) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "IPv4-mapped target on an IPv6 only socket",
+                    ));
+                }
+            }
+            if let (0, Some(scope_id)) = (v6.scope_id(), self.scope_id) {
+                v6.set_scope_id(scope_id);
+            }
+        }
+        Ok(target)
+    }
+
+    /// Create a socket bound to the local address and interface for sending to `target`
+    pub(super) fn bound_socket(&self, target: SocketAddr) -> Result<Socket, io::Error> {
+        let bind = self.bind.unwrap_or(match target {
+            SocketAddr::V4(_) => SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0),
+            SocketAddr::V6(_) => SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0),
+        });
+        if bind.is_ipv4() != target.is_ipv4() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "bind address and target are of different address families",
+            ));
+        }
+
+        let socket = Socket::new(
+            Domain::for_address(target),
+            Type::DGRAM,
+            Some(Protocol::UDP),
+        )?;
+        if target.is_ipv6() {
+            let mapped = matches!(target, SocketAddr::V6(v6) if v6.ip().to_ipv4_mapped().is_some());
+            if let Some(only_v6) = self.only_v6 {
+                socket.set_only_v6(only_v6)?;
+            } else if mapped {
+                // dual-stack isn't the default everywhere
+                socket.set_only_v6(false)?;
+            }
+        }
+        #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+        if let Some(interface) = &self.interface {
+            socket.bind_device(Some(interface.as_bytes()))?;
+        }
+        socket.bind(&bind.into())?;
+
+        Ok(socket)
+    }
+
+    /// Create a [UdpTransport](super::UdpTransport) sending to `target`
+    #[cfg(feature = "transport-udp")]
+    pub fn connect<A: std::net::ToSocketAddrs>(
+        self,
+        target: A,
+    ) -> Result<super::UdpTransport, io::Error> {
+        let socket = self.socket(target.to_socket_addrs()?)?;
+        Ok(super::UdpTransport::from_socket(
+            socket,
+            self.recv_buffer_size,
+        ))
+    }
+
+    /// Create a [UdpTransportAsync](super::UdpTransportAsync) sending to `target`
+    #[cfg(feature = "transport-udp-async")]
+    pub async fn connect_async<A: tokio::net::ToSocketAddrs>(
+        self,
+        target: A,
+    ) -> Result<super::UdpTransportAsync, io::Error> {
+        let socket = self.socket(tokio::net::lookup_host(target).await?)?;
+        socket.set_nonblocking(true)?;
+        let socket = tokio::net::UdpSocket::from_std(socket)?;
+        Ok(super::UdpTransportAsync::from_socket(
+            socket,
+            self.recv_buffer_size,
+        ))
+    }
+}
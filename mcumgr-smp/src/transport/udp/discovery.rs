@@ -0,0 +1,270 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::codec::CborCodec;
+use crate::os_management::{EchoRequest, GetInfoRequest};
+use crate::response::{ResponseError, SmpResponse};
+use crate::smp::SmpError;
+use crate::transport::error::Error;
+use crate::transport::udp::UdpTransportBuilder;
+use crate::{OpCode, SmpFrame, SmpHeader, SmpRequest, SmpVersion};
+use serde::de::DeserializeOwned;
+use std::net::{Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+/// Port of the SMP UDP transport
+pub const SMP_UDP_PORT: u16 = 1337;
+
+/// IPv6 link-local all-nodes multicast address.
+/// Sending to it needs the interface as scope id, e.g. `[ff02::1%2]:1337`.
+pub const ALL_NODES_V6: Ipv6Addr = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 1);
+
+/// Text sent in [Probe::Echo] requests
+const ECHO_TEXT: &str = "smp discovery";
+
+/// Request sent to find devices
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Probe {
+    /// OS echo, answered by every device with the OS group enabled
+    Echo,
+    /// OS info with all fields, like `uname -a`
+    #[default]
+    Info,
+}
+
+/// A device that answered a discovery request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Responder {
+    pub addr: SocketAddr,
+    /// Output of `os info`, or the echoed text for [Probe::Echo].
+    /// Devices without `os info` support answer with an error.
+    pub identity: Result<String, IdentityError>,
+}
+
+/// Why the identity of a [Responder] is unknown
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum IdentityError {
+    /// The device answered with an error
+    #[error("{0}")]
+    Response(#[from] ResponseError),
+    /// The response didn't fit into the receive buffer of the given size
+    #[error("response larger than the receive buffer of {0} bytes")]
+    Truncated(usize),
+}
+
+impl Probe {
+    /// Encode the request as SMP version 1, which all devices understand
+    fn encode(self, sequence: u8) -> Result<Vec<u8>, SmpError> {
+        match self {
+            Probe::Echo => EchoRequest {
+                d: ECHO_TEXT.into(),
+            }
+            .into_frame(sequence)
+            .encode_as::<CborCodec>(SmpVersion::V1, sequence),
+            Probe::Info => GetInfoRequest { format: "a".into() }
+                .into_frame(sequence)
+                .encode_as::<CborCodec>(SmpVersion::V1, sequence),
+        }
+    }
+
+    /// Whether `frame` starts with the header of the response to the probe,
+    /// the payload may be cut off
+    fn is_response(self, sequence: u8, frame: &[u8]) -> bool {
+        match self {
+            Probe::Echo => is_response::<EchoRequest>(sequence, frame),
+            Probe::Info => is_response::<GetInfoRequest>(sequence, frame),
+        }
+    }
+
+    /// Identity of the device from its response, `None` if `frame` isn't the response to the probe
+    fn identity(self, sequence: u8, frame: &[u8]) -> Option<Result<String, ResponseError>> {
+        match self {
+            Probe::Echo => {
+                decode_response::<EchoRequest>(sequence, frame).map(|res| res.map(|echo| echo.r))
+            }
+            Probe::Info => decode_response::<GetInfoRequest>(sequence, frame)
+                .map(|res| res.map(|info| info.output)),
+        }
+    }
+}
+
+fn is_response<R: SmpRequest>(sequence: u8, frame: &[u8]) -> bool {
+    let Some(header) = frame.get(..SmpHeader::LEN) else {
+        return false;
+    };
+    // parse only the header, with an empty payload
+    let mut header: [u8; SmpHeader::LEN] = header.try_into().unwrap();
+    header[2..4].fill(0);
+    SmpHeader::parse(&header).is_ok_and(|header| {
+        matches!(
+            header.operation(),
+            OpCode::ReadResponse | OpCode::WriteResponse
+        ) && header.group() == R::GROUP
+            && header.command() == R::COMMAND
+            && header.sequence() == sequence
+    })
+}
+
+fn decode_response<R: SmpRequest>(
+    sequence: u8,
+    frame: &[u8],
+) -> Option<Result<R::Response, ResponseError>>
+where
+    R::Response: DeserializeOwned,
+{
+    if !is_response::<R>(sequence, frame) {
+        return None;
+    }
+
+    let response = SmpFrame::<SmpResponse<R::Response>>::decode_with_cbor(frame).ok()?;
+    Some(response.data.into_result())
+}
+
+/// Collects the responses of a discovery, one per address
+struct Responders {
+    probe: Probe,
+    sequence: u8,
+    responders: Vec<Responder>,
+}
+
+impl Responders {
+    fn new(probe: Probe) -> Self {
+        // vary the sequence number so late replies to an earlier discovery are ignored
+        let sequence = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |time| time.subsec_nanos() as u8);
+        Self {
+            probe,
+            sequence,
+            responders: Vec::new(),
+        }
+    }
+
+    fn request(&self) -> Result<Vec<u8>, SmpError> {
+        self.probe.encode(self.sequence)
+    }
+
+    /// Add the device at `addr` if `datagram` is the response to the probe.
+    /// `datagram` is read into a buffer one byte larger than `recv_buffer_size` to detect truncation.
+    fn add(&mut self, addr: SocketAddr, datagram: &[u8], recv_buffer_size: usize) {
+        if self.responders.iter().any(|r| r.addr == addr) {
+            return;
+        }
+        let identity = if datagram.len() > recv_buffer_size {
+            if !self.probe.is_response(self.sequence, datagram) {
+                return;
+            }
+            Err(IdentityError::Truncated(recv_buffer_size))
+        } else {
+            match self.probe.identity(self.sequence, datagram) {
+                Some(identity) => identity.map_err(IdentityError::from),
+                None => return,
+            }
+        };
+        self.responders.push(Responder { addr, identity });
+    }
+}
+
+impl UdpTransportBuilder {
+    /// Send `probe` to a broadcast or multicast address, e.g. `255.255.255.255:1337` or
+    /// `[ff02::1%2]:1337`, and collect the devices answering within `window`.
+    ///
+    /// Unicast addresses work as well, e.g. to check a single device.
+    /// The bind address, interface, scope id and receive buffer size of the builder are used.
+    /// Devices answering with a response larger than the buffer are listed with
+    /// [IdentityError::Truncated].
+    #[cfg(feature = "transport-udp")]
+    pub fn discover(
+        &self,
+        target: SocketAddr,
+        probe: Probe,
+        window: Duration,
+    ) -> Result<Vec<Responder>, Error> {
+        use std::io;
+        use std::net::UdpSocket;
+        use std::time::Instant;
+
+        let mut responders = Responders::new(probe);
+        self.check()?;
+        let target = self.target(target)?;
+        let socket = self.bound_socket(target)?;
+        let socket = UdpSocket::from(socket);
+        socket.set_broadcast(target.is_ipv4())?;
+        socket.send_to(&responders.request()?, target)?;
+
+        let deadline = Instant::now() + window;
+        let mut buf = vec![0; self.recv_buffer_size + 1];
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            socket.set_read_timeout(Some(remaining))?;
+            match socket.recv_from(&mut buf) {
+                Ok((len, addr)) => responders.add(addr, &buf[..len], self.recv_buffer_size),
+                Err(err)
+                    if matches!(
+                        err.kind(),
+                        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    break
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        Ok(responders.responders)
+    }
+
+    /// Async version of [discover](Self::discover)
+    #[cfg(feature = "transport-udp-async")]
+    pub async fn discover_async(
+        &self,
+        target: SocketAddr,
+        probe: Probe,
+        window: Duration,
+    ) -> Result<Vec<Responder>, Error> {
+        use tokio::net::UdpSocket;
+        use tokio::time::{timeout_at, Instant};
+
+        let mut responders = Responders::new(probe);
+        self.check()?;
+        let target = self.target(target)?;
+        let socket = self.bound_socket(target)?;
+        socket.set_nonblocking(true)?;
+        let socket = UdpSocket::from_std(socket.into())?;
+        socket.set_broadcast(target.is_ipv4())?;
+        socket.send_to(&responders.request()?, target).await?;
+
+        let deadline = Instant::now() + window;
+        let mut buf = vec![0; self.recv_buffer_size + 1];
+        while let Ok(received) = timeout_at(deadline, socket.recv_from(&mut buf)).await {
+            let (len, addr) = received?;
+            responders.add(addr, &buf[..len], self.recv_buffer_size);
+        }
+
+        Ok(responders.responders)
+    }
+}
+
+/// Discover devices with the default socket options, see [UdpTransportBuilder::discover]
+#[cfg(feature = "transport-udp")]
+pub fn discover(
+    target: SocketAddr,
+    probe: Probe,
+    window: Duration,
+) -> Result<Vec<Responder>, Error> {
+    UdpTransportBuilder::new().discover(target, probe, window)
+}
+
+/// Discover devices with the default socket options, see [UdpTransportBuilder::discover]
+#[cfg(feature = "transport-udp-async")]
+pub async fn discover_async(
+    target: SocketAddr,
+    probe: Probe,
+    window: Duration,
+) -> Result<Vec<Responder>, Error> {
+    UdpTransportBuilder::new()
+        .discover_async(target, probe, window)
+        .await
+}
@@ -0,0 +1,65 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::{OpCode, SmpHeader};
+
+/// Requests sent without a response so far, kept for retransmission.
+///
+/// Received frames are only passed on if they answer one of these requests, so
+/// duplicate responses to retransmitted requests and late responses to requests
+/// that timed out are dropped.
+#[derive(Default)]
+pub(crate) struct InFlight {
+    frames: Vec<Vec<u8>>,
+}
+
+impl InFlight {
+    /// Remember a sent request
+    pub fn sent(&mut self, frame: &[u8]) {
+        let Ok(header) = SmpHeader::parse(frame) else {
+            return;
+        };
+        // the sequence number wrapped around, the old request won't be answered anymore
+        self.frames
+            .retain(|f| SmpHeader::parse(f).map(|h| h.sequence()).ok() != Some(header.sequence()));
+        self.frames.push(frame.to_vec());
+    }
+
+    /// Requests to send again after a timeout
+    pub fn frames(&self) -> &[Vec<u8>] {
+        &self.frames
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Give up on all requests
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Check if a received frame answers a request in flight, which is then no longer waited for.
+    /// Everything is accepted if no request was sent, e.g. when only receiving.
+    pub fn accept(&mut self, frame: &[u8]) -> bool {
+        if self.frames.is_empty() {
+            return true;
+        }
+        let Ok(header) = SmpHeader::parse(frame) else {
+            return false;
+        };
+        let is_response = matches!(
+            header.operation(),
+            OpCode::ReadResponse | OpCode::WriteResponse
+        );
+        let pos = self.frames.iter().position(|f| {
+            SmpHeader::parse(f).map(|h| h.sequence()).ok() == Some(header.sequence())
+        });
+        match pos {
+            Some(pos) if is_response => {
+                self.frames.remove(pos);
+                true
+            }
+            _ => false,
+        }
+    }
+}
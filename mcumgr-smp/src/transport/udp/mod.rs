@@ -1,3 +1,10 @@
+pub mod builder;
+pub use builder::{AddressFamily, UdpTransportBuilder};
+
+/// Find devices by sending a request to a broadcast or multicast address
+#[cfg(feature = "payload-cbor")]
+pub mod discovery;
+
 #[cfg(feature = "transport-udp-async")]
 pub mod udp_async;
 #[cfg(feature = "transport-udp-async")]
@@ -7,3 +14,5 @@ pub use udp_async::UdpTransportAsync;
 pub mod udp_sync;
 #[cfg(feature = "transport-udp")]
 pub use udp_sync::UdpTransport;
+
+mod in_flight;
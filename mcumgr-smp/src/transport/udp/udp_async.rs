@@ -1,26 +1,61 @@
 // Author: Sascha Zenglein <zenglein@gessler.de>
 // Copyright (c) 2023 Gessler GmbH.
 
+use super::in_flight::InFlight;
+use super::UdpTransportBuilder;
 use crate::transport::error::Error;
 use crate::transport::smp::SmpTransportAsync;
 use async_trait::async_trait;
 use std::io;
-use std::net::{Ipv6Addr, SocketAddr};
+use std::time::Duration;
 use tokio::net::{ToSocketAddrs, UdpSocket};
+use tokio::time::{timeout_at, Instant};
 
+/// UDP transport on tokio.
+///
+/// With a [recv_timeout](Self::recv_timeout), requests without a response are sent again
+/// up to [set_retries](Self::set_retries) times before [Error::Timeout] is returned.
+/// Duplicate responses to retransmitted requests and late responses are dropped.
 pub struct UdpTransportAsync {
     socket: UdpSocket,
     buf: Vec<u8>,
+    recv_buffer_size: usize,
+    timeout: Option<Duration>,
+    retries: usize,
+    in_flight: InFlight,
 }
 
 impl UdpTransportAsync {
+    /// Send to `target` from an ephemeral port, see [builder](Self::builder) for more options
     pub async fn new<A: ToSocketAddrs>(target: A) -> Result<Self, io::Error> {
-        let socket = UdpSocket::bind(SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0)).await?;
-        socket.connect(target).await?;
+        UdpTransportBuilder::new().connect_async(target).await
+    }
+
+    pub fn builder() -> UdpTransportBuilder {
+        UdpTransportBuilder::new()
+    }
 
-        let buf = vec![0; 1500];
+    pub(super) fn from_socket(socket: UdpSocket, recv_buffer_size: usize) -> Self {
+        Self {
+            socket,
+            // one byte more than allowed tells a truncated datagram from one that fits exactly
+            buf: vec![0; recv_buffer_size + 1],
+            recv_buffer_size,
+            timeout: None,
+            retries: 0,
+            in_flight: InFlight::default(),
+        }
+    }
+
+    /// Time to wait for a response before sending the request again or failing with
+    /// [Error::Timeout]. `None` waits forever.
+    pub fn recv_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
 
-        Ok(Self { socket, buf })
+    /// Number of times a request is sent again if no response arrives in time
+    pub fn set_retries(&mut self, retries: usize) {
+        self.retries = retries;
     }
 }
 
@@ -28,12 +63,42 @@ impl UdpTransportAsync {
 impl SmpTransportAsync for UdpTransportAsync {
     async fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
         self.socket.send(&frame).await?;
+        self.in_flight.sent(&frame);
         Ok(())
     }
 
     async fn receive(&mut self) -> Result<Vec<u8>, Error> {
-        let len = self.socket.recv(&mut self.buf).await?;
+        let mut retries = 0;
+        let mut deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            let received = match deadline {
+                Some(deadline) => timeout_at(deadline, self.socket.recv(&mut self.buf)).await,
+                None => Ok(self.socket.recv(&mut self.buf).await),
+            };
 
-        Ok(Vec::from(&self.buf[0..len]))
+            match received {
+                Ok(len) => {
+                    let len = len?;
+                    if len > self.recv_buffer_size {
+                        return Err(Error::Truncated(self.recv_buffer_size));
+                    }
+                    let frame = &self.buf[0..len];
+                    if self.in_flight.accept(frame) {
+                        return Ok(Vec::from(frame));
+                    }
+                }
+                Err(_) if retries < self.retries && !self.in_flight.is_empty() => {
+                    retries += 1;
+                    for frame in self.in_flight.frames() {
+                        self.socket.send(frame).await?;
+                    }
+                    deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+                }
+                Err(_) => {
+                    self.in_flight.clear();
+                    return Err(Error::Timeout);
+                }
+            }
+        }
     }
 }
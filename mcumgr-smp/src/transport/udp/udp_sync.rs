@@ -1,42 +1,122 @@
 // Author: Sascha Zenglein <zenglein@gessler.de>
 // Copyright (c) 2023 Gessler GmbH.
 
+use super::in_flight::InFlight;
+use super::UdpTransportBuilder;
 use crate::transport::error::Error;
 use crate::transport::smp::SmpTransport;
 use std::io;
-use std::net::{Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
-use std::time::Duration;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
 
+/// UDP transport.
+///
+/// With a [recv_timeout](Self::recv_timeout), requests without a response are sent again
+/// up to [set_retries](Self::set_retries) times before [Error::Timeout] is returned.
+/// Duplicate responses to retransmitted requests and late responses are dropped.
 pub struct UdpTransport {
     socket: UdpSocket,
     buf: Vec<u8>,
+    recv_buffer_size: usize,
+    timeout: Option<Duration>,
+    retries: usize,
+    in_flight: InFlight,
 }
 
 impl UdpTransport {
+    /// Send to `target` from an ephemeral port, see [builder](Self::builder) for more options
     pub fn new<A: ToSocketAddrs>(target: A) -> Result<Self, io::Error> {
-        let socket = UdpSocket::bind(SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0))?;
-        socket.connect(target)?;
+        UdpTransportBuilder::new().connect(target)
+    }
 
-        let buf = vec![0; 1500];
+    pub fn builder() -> UdpTransportBuilder {
+        UdpTransportBuilder::new()
+    }
 
-        Ok(Self { socket, buf })
+    pub(super) fn from_socket(socket: UdpSocket, recv_buffer_size: usize) -> Self {
+        Self {
+            socket,
+            // one byte more than allowed tells a truncated datagram from one that fits exactly
+            buf: vec![0; recv_buffer_size + 1],
+            recv_buffer_size,
+            timeout: None,
+            retries: 0,
+            in_flight: InFlight::default(),
+        }
     }
 
+    /// Time to wait for a response before sending the request again or failing with
+    /// [Error::Timeout]. `None` waits forever.
     pub fn recv_timeout(&mut self, timeout: Option<Duration>) -> Result<(), Error> {
+        // rejects a zero timeout
         self.socket.set_read_timeout(timeout)?;
+        self.timeout = timeout;
         Ok(())
     }
+
+    /// Number of times a request is sent again if no response arrives in time
+    pub fn set_retries(&mut self, retries: usize) {
+        self.retries = retries;
+    }
+
+    /// Wait for a datagram until `deadline`, `None` if it passed
+    fn recv_until(&mut self, deadline: Option<Instant>) -> Result<Option<usize>, Error> {
+        if let Some(deadline) = deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            self.socket.set_read_timeout(Some(remaining))?;
+        }
+
+        match self.socket.recv(&mut self.buf) {
+            Ok(len) => Ok(Some(len)),
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
 }
 
 impl SmpTransport for UdpTransport {
     fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
         self.socket.send(&frame)?;
+        self.in_flight.sent(&frame);
         Ok(())
     }
 
     fn receive(&mut self) -> Result<Vec<u8>, Error> {
-        let len = self.socket.recv(&mut self.buf)?;
-
-        Ok(Vec::from(&self.buf[0..len]))
+        let mut retries = 0;
+        let mut deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            match self.recv_until(deadline)? {
+                Some(len) => {
+                    if len > self.recv_buffer_size {
+                        return Err(Error::Truncated(self.recv_buffer_size));
+                    }
+                    let frame = &self.buf[0..len];
+                    if self.in_flight.accept(frame) {
+                        return Ok(Vec::from(frame));
+                    }
+                }
+                None if retries < self.retries && !self.in_flight.is_empty() => {
+                    retries += 1;
+                    for frame in self.in_flight.frames() {
+                        self.socket.send(frame)?;
+                    }
+                    deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+                }
+                None => {
+                    self.in_flight.clear();
+                    return Err(Error::Timeout);
+                }
+            }
+        }
     }
 }
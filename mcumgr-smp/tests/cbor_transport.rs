@@ -0,0 +1,253 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Tests for sequence numbers and version negotiation of the CBOR transports.
+
+#![cfg(all(feature = "payload-cbor", feature = "async"))]
+
+use async_trait::async_trait;
+use mcumgr_smp::codec::RawCodec;
+use mcumgr_smp::transport::error::Error;
+use mcumgr_smp::transport::smp::{
+    CborSmpTransport, CborSmpTransportAsync, SmpTransport, SmpTransportAsync,
+};
+use mcumgr_smp::{Group, OpCode, SmpError, SmpFrame, SmpHeader, SmpVersion};
+use std::collections::VecDeque;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct DeviceState {
+    /// version of the responses
+    version: SmpVersion,
+    /// version and sequence number of each request
+    requests: Vec<(SmpVersion, u8)>,
+    /// indices of the requests answered only after the next request
+    late: Vec<usize>,
+    held: Option<Vec<u8>>,
+    responses: VecDeque<Vec<u8>>,
+}
+
+/// A device echoing the payload of each request
+#[derive(Clone, Default)]
+struct Device(Arc<Mutex<DeviceState>>);
+
+impl Device {
+    fn new(version: SmpVersion, late: Vec<usize>) -> Self {
+        Self(Arc::new(Mutex::new(DeviceState {
+            version,
+            late,
+            ..Default::default()
+        })))
+    }
+
+    fn requests(&self) -> Vec<(SmpVersion, u8)> {
+        self.0.lock().unwrap().requests.clone()
+    }
+}
+
+impl SmpTransport for Device {
+    fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
+        let mut state = self.0.lock().unwrap();
+        let header = SmpHeader::parse(&frame)?;
+        state.requests.push((header.version(), header.sequence()));
+
+        let mut response = SmpFrame::new(
+            OpCode::ReadResponse,
+            header.sequence(),
+            header.group(),
+            header.command(),
+            header.payload().to_vec(),
+        );
+        response.version = state.version;
+        let response = response.encode::<RawCodec>()?;
+
+        if state.late.contains(&(state.requests.len() - 1)) {
+            state.held = Some(response);
+        } else {
+            if let Some(held) = state.held.take() {
+                state.responses.push_back(held);
+            }
+            state.responses.push_back(response);
+        }
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        let response = self.0.lock().unwrap().responses.pop_front();
+        response.ok_or_else(|| io::Error::from(io::ErrorKind::TimedOut).into())
+    }
+}
+
+#[async_trait]
+impl SmpTransportAsync for Device {
+    async fn send(&mut self, frame: Vec<u8>) -> Result<(), Error> {
+        SmpTransport::send(self, frame)
+    }
+
+    async fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        SmpTransport::receive(self)
+    }
+}
+
+fn request(payload: u8) -> SmpFrame<Vec<u8>> {
+    // the sequence number is assigned by the transport
+    SmpFrame::new(OpCode::ReadRequest, 0xaa, Group::Default, 0, vec![payload])
+}
+
+#[test]
+fn v2_by_default() {
+    let device = Device::new(SmpVersion::V2, vec![]);
+    let mut transport = CborSmpTransport::<RawCodec>::with_codec(Box::new(device.clone()));
+    assert_eq!(transport.version(), SmpVersion::V2);
+
+    for payload in 0..2 {
+        transport
+            .transceive_frame::<_, Vec<u8>>(&request(payload))
+            .unwrap();
+    }
+    // frames built for version 1 are sent as such
+    let mut v1_request = request(2);
+    v1_request.version = SmpVersion::V1;
+    transport
+        .transceive_frame::<_, Vec<u8>>(&v1_request)
+        .unwrap();
+
+    assert_eq!(transport.version(), SmpVersion::V2);
+    assert_eq!(
+        device.requests(),
+        [
+            (SmpVersion::V2, 0),
+            (SmpVersion::V2, 1),
+            (SmpVersion::V1, 2)
+        ]
+    );
+}
+
+#[test]
+fn v1_fallback() {
+    let device = Device::new(SmpVersion::V1, vec![]);
+    let mut transport = CborSmpTransport::<RawCodec>::with_codec(Box::new(device.clone()));
+
+    let response = transport
+        .transceive_frame::<_, Vec<u8>>(&request(1))
+        .unwrap();
+    assert_eq!(response.version, SmpVersion::V1);
+    assert_eq!(transport.version(), SmpVersion::V1);
+
+    transport
+        .transceive_frame::<_, Vec<u8>>(&request(2))
+        .unwrap();
+    assert_eq!(
+        device.requests(),
+        [(SmpVersion::V2, 0), (SmpVersion::V1, 1)]
+    );
+}
+
+#[test]
+fn late_reply_is_dropped() {
+    let device = Device::new(SmpVersion::V2, vec![0]);
+    let mut transport = CborSmpTransport::<RawCodec>::with_codec(Box::new(device.clone()));
+
+    assert!(transport
+        .transceive_frame::<_, Vec<u8>>(&request(1))
+        .is_err());
+    // the reply to the first request arrives before the response to the second one
+    let response = transport
+        .transceive_frame::<_, Vec<u8>>(&request(2))
+        .unwrap();
+    assert_eq!((response.sequence, response.data), (1, vec![2]));
+    assert_eq!(
+        device.requests(),
+        [(SmpVersion::V2, 0), (SmpVersion::V2, 1)]
+    );
+}
+
+#[test]
+fn outstanding_responses_are_kept() {
+    let device = Device::new(SmpVersion::V2, vec![]);
+    let mut transport = CborSmpTransport::<RawCodec>::with_codec(Box::new(device));
+
+    let first = transport.send_frame(&request(1)).unwrap();
+    let second = transport.send_frame(&request(2)).unwrap();
+
+    let response: SmpFrame<Vec<u8>> = transport.receive_frame(second).unwrap();
+    assert_eq!(response.data, [2]);
+    let response: SmpFrame<Vec<u8>> = transport.receive_frame(first).unwrap();
+    assert_eq!(response.data, [1]);
+}
+
+#[test]
+fn sequence_wraps_around() {
+    let device = Device::new(SmpVersion::V2, vec![]);
+    let mut transport = CborSmpTransport::<RawCodec>::with_codec(Box::new(device.clone()));
+
+    for i in 0..=257usize {
+        let response = transport
+            .transceive_frame::<_, Vec<u8>>(&request(i as u8))
+            .unwrap();
+        assert_eq!(response.data, [i as u8]);
+    }
+
+    let sequences: Vec<u8> = device.requests().iter().map(|(_, seq)| *seq).collect();
+    assert_eq!(sequences[254..], [254, 255, 0, 1]);
+}
+
+#[test]
+fn receive_without_request() {
+    let device = Device::new(SmpVersion::V2, vec![]);
+    let mut transport = CborSmpTransport::<RawCodec>::with_codec(Box::new(device));
+
+    let err = transport.receive_frame::<Vec<u8>>(5).unwrap_err();
+    assert!(matches!(err, Error::Smp(SmpError::UnexpectedSeq)));
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_cbor_functions() {
+    let device = Device::new(SmpVersion::V2, vec![]);
+    let mut transport = CborSmpTransport::<RawCodec>::with_codec(Box::new(device));
+
+    let response: SmpFrame<Vec<u8>> = transport.transceive_cbor(&request(1), false).unwrap();
+    assert_eq!(response.data, [1]);
+
+    let first = transport.send_cbor(&request(2)).unwrap();
+    transport.send_cbor(&request(3)).unwrap();
+    // `None` receives the response to the last sent frame
+    let response: SmpFrame<Vec<u8>> = transport.receive_cbor(None).unwrap();
+    assert_eq!(response.data, [3]);
+    let response: SmpFrame<Vec<u8>> = transport.receive_cbor(Some(first)).unwrap();
+    assert_eq!(response.data, [2]);
+}
+
+#[tokio::test]
+async fn v1_fallback_after_late_reply() {
+    let device = Device::new(SmpVersion::V1, vec![0]);
+    let mut transport = CborSmpTransportAsync::<RawCodec>::with_codec(Box::new(device.clone()));
+
+    assert!(transport
+        .transceive_frame::<_, Vec<u8>>(&request(1))
+        .await
+        .is_err());
+    assert_eq!(transport.version(), SmpVersion::V2);
+
+    // the dropped late reply doesn't count, the response to the second request does
+    let response = transport
+        .transceive_frame::<_, Vec<u8>>(&request(2))
+        .await
+        .unwrap();
+    assert_eq!((response.sequence, response.data), (1, vec![2]));
+    assert_eq!(transport.version(), SmpVersion::V1);
+
+    transport
+        .transceive_frame::<_, Vec<u8>>(&request(3))
+        .await
+        .unwrap();
+    assert_eq!(
+        device.requests(),
+        [
+            (SmpVersion::V2, 0),
+            (SmpVersion::V2, 1),
+            (SmpVersion::V1, 2)
+        ]
+    );
+}
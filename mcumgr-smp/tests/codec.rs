@@ -0,0 +1,181 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Round trips of frames through the payload codecs, and encoding into caller buffers.
+
+use mcumgr_smp::codec::{DecodePayload, EncodePayload, PayloadCodec, RawCodec};
+use mcumgr_smp::{Group, OpCode, SmpError, SmpFrame, SmpVersion};
+
+fn roundtrip<C, T>(frame: &SmpFrame<T>) -> (Vec<u8>, SmpFrame<T>)
+where
+    C: PayloadCodec + EncodePayload<T> + DecodePayload<T>,
+{
+    let bytes = frame.encode::<C>().unwrap();
+    let decoded = SmpFrame::<T>::decode::<C>(&bytes).unwrap();
+    assert_eq!(
+        (
+            decoded.operation,
+            decoded.version,
+            decoded.flags,
+            decoded.group,
+            decoded.sequence,
+            decoded.command
+        ),
+        (
+            frame.operation,
+            frame.version,
+            frame.flags,
+            frame.group,
+            frame.sequence,
+            frame.command
+        ),
+        "{} header",
+        C::NAME
+    );
+    (bytes, decoded)
+}
+
+#[test]
+fn raw_roundtrip() {
+    let payloads: [Vec<u8>; 3] = [vec![], vec![0], (0..=255).collect()];
+    for payload in payloads {
+        let mut frame = SmpFrame::new(OpCode::WriteRequest, 9, Group::Custom(300), 4, payload);
+        frame.version = SmpVersion::V1;
+        frame.flags = 0x5a;
+
+        let (bytes, decoded) = roundtrip::<RawCodec, _>(&frame);
+        // the payload is passed through unchanged
+        assert_eq!(bytes[8..], frame.data);
+        assert_eq!(decoded.data, frame.data);
+    }
+}
+
+#[cfg(feature = "payload-json")]
+#[test]
+fn json_roundtrip() {
+    use mcumgr_smp::codec::JsonCodec;
+    use mcumgr_smp::os_management::{echo, EchoRequest};
+    use mcumgr_smp::shell_management::{shell_command, ShellCommand};
+
+    let frame = echo(3, "hello".into());
+    let (bytes, decoded) = roundtrip::<JsonCodec, EchoRequest>(&frame);
+    assert_eq!(bytes[8..], *br#"{"d":"hello"}"#);
+    assert_eq!(bytes[2..4], 13u16.to_be_bytes());
+    assert_eq!(decoded.data.d, "hello");
+
+    let frame = shell_command(4, vec!["kernel".into(), "uptime".into()]);
+    let (_, decoded) = roundtrip::<JsonCodec, ShellCommand>(&frame);
+    assert_eq!(decoded.data.argv, ["kernel", "uptime"]);
+
+    let invalid = SmpFrame::new(
+        OpCode::ReadRequest,
+        0,
+        Group::Default,
+        0,
+        b"{\"d\":".to_vec(),
+    )
+    .encode::<RawCodec>()
+    .unwrap();
+    assert!(SmpFrame::<EchoRequest>::decode::<JsonCodec>(&invalid).is_err());
+}
+
+#[test]
+fn encode_into_buffer_too_small() {
+    let frame = SmpFrame::new(OpCode::WriteRequest, 1, Group::Default, 0, vec![1, 2, 3]);
+    let mut buf = [0u8; 16];
+    assert_eq!(frame.encode_into::<RawCodec>(&mut buf).unwrap(), 11);
+    assert_eq!(buf[..11], frame.encode::<RawCodec>().unwrap());
+
+    for len in 0..11 {
+        assert!(matches!(
+            frame.encode_into::<RawCodec>(&mut buf[..len]),
+            Err(SmpError::BufferTooSmall)
+        ));
+    }
+}
+
+#[cfg(feature = "payload-cbor")]
+mod cbor {
+    use mcumgr_smp::application_management::{ImageUploadRequest, ImageWriter};
+    use mcumgr_smp::os_management::echo;
+    use mcumgr_smp::{Group, OpCode, SmpError, SmpFrame};
+    use serde::{Serialize, Serializer};
+
+    #[test]
+    fn encode_into_with_cbor() {
+        let frame = echo(5, "hello".into());
+        let expected = frame.encode_with_cbor().unwrap();
+
+        let mut buf = [0xffu8; 64];
+        let len = frame.encode_into_with_cbor(&mut buf).unwrap();
+        assert_eq!(buf[..len], expected);
+
+        #[cfg(feature = "std")]
+        {
+            let mut written = Vec::new();
+            let codec_len = frame
+                .encode_to::<mcumgr_smp::codec::CborCodec>(&mut written)
+                .unwrap();
+            assert_eq!((codec_len, written), (len, expected.clone()));
+        }
+
+        // an exactly sized buffer is enough
+        assert_eq!(frame.encode_into_with_cbor(&mut buf[..len]).unwrap(), len);
+        // the header or the payload doesn't fit, the full slice is reported as too small
+        for short in 0..len {
+            assert!(
+                matches!(
+                    frame.encode_into_with_cbor(&mut buf[..short]),
+                    Err(SmpError::BufferTooSmall)
+                ),
+                "{} bytes",
+                short
+            );
+        }
+    }
+
+    /// A payload that fails to encode
+    struct Unencodable;
+
+    impl Serialize for Unencodable {
+        fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("not encodable"))
+        }
+    }
+
+    #[test]
+    fn encode_into_payload_error() {
+        let frame = SmpFrame::new(OpCode::WriteRequest, 0, Group::Default, 0, Unencodable);
+        let mut buf = [0u8; 64];
+        assert!(matches!(
+            frame.encode_into_with_cbor(&mut buf),
+            Err(SmpError::PayloadEncodingError(_))
+        ));
+    }
+
+    #[test]
+    fn image_writer_into_buffer() {
+        let image = [0x5au8; 100];
+        let hash = [0x11u8; 32];
+        let mut writer = ImageWriter::new(Some(1), image.len(), Some(&hash), false);
+        let mut buf = [0u8; 128];
+
+        // the first chunk carries the image length and hash and doesn't fit
+        assert!(matches!(
+            writer.write_chunk_into(&image, &mut buf),
+            Err(SmpError::BufferTooSmall)
+        ));
+        assert_eq!((writer.offset, writer.sequence), (0, 0));
+
+        let len = writer.write_chunk_into(&image[..40], &mut buf).unwrap();
+        let frame = SmpFrame::<ImageUploadRequest>::decode_with_cbor(&buf[..len]).unwrap();
+        assert_eq!(frame.sequence, 1);
+        assert_eq!((frame.data.off, frame.data.len), (0, Some(100)));
+        assert_eq!(frame.data.sha.as_deref(), Some(hash.as_slice()));
+        assert_eq!(frame.data.data, image[..40]);
+
+        let len = writer.write_chunk_into(&image[40..], &mut buf).unwrap();
+        let frame = SmpFrame::<ImageUploadRequest>::decode_with_cbor(&buf[..len]).unwrap();
+        assert_eq!((frame.data.off, frame.data.len), (40, None));
+        assert_eq!(writer.offset, 100);
+    }
+}
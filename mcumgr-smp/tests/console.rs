@@ -0,0 +1,259 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Loopback tests for the console framed transports over TCP, Unix sockets and PTYs.
+
+#![cfg(all(feature = "transport-console", feature = "transport-console-async"))]
+
+use mcumgr_smp::transport::console::{ConsoleTransport, ConsoleTransportAsync};
+use mcumgr_smp::transport::error::Error;
+use mcumgr_smp::transport::smp::{SmpTransport, SmpTransportAsync};
+use mcumgr_smp::transport::smp_framing::{ConsoleInput, SmpTransportDecoder, SmpTransportEncoder};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+const LOG_LINE: &[u8] = b"[00:00:01.000] <inf> app: hello\n";
+
+/// Encode `frame` with the console framing, preceded by a log line
+fn console_lines(frame: &[u8]) -> Vec<u8> {
+    let mut encoder = SmpTransportEncoder::new(frame);
+    let mut out = LOG_LINE.to_vec();
+    let mut buf = [0; 127];
+    while !encoder.is_complete() {
+        let len = encoder.write_line(&mut buf).unwrap();
+        out.extend_from_slice(&buf[..len]);
+    }
+    out
+}
+
+/// A frame with a wrong CRC followed by a valid one, both preceded by a log line
+fn invalid_then_valid(valid: &[u8]) -> Vec<u8> {
+    let mut corrupted = console_lines(valid);
+    // change a base64 character of the payload
+    let pos = LOG_LINE.len() + 8;
+    corrupted[pos] = if corrupted[pos] == b'A' { b'B' } else { b'A' };
+    corrupted.extend_from_slice(&console_lines(valid));
+    corrupted
+}
+
+fn next_frame(decoder: &mut SmpTransportDecoder) -> Option<Vec<u8>> {
+    while let Some(input) = decoder.next_input() {
+        if let ConsoleInput::Frame(frame) = input.unwrap() {
+            return Some(frame);
+        }
+    }
+    None
+}
+
+/// A device echoing `count` frames. Returns the stream, so a PTY isn't hung up
+/// before the host read the last response.
+fn echo<S: Read + Write>(mut stream: S, count: usize) -> S {
+    let mut decoder = SmpTransportDecoder::new();
+    let mut buf = [0; 256];
+    for _ in 0..count {
+        let frame = loop {
+            if let Some(frame) = next_frame(&mut decoder) {
+                break frame;
+            }
+            let len = stream.read(&mut buf).unwrap();
+            decoder.push(&buf[..len]);
+        };
+        stream.write_all(&console_lines(&frame)).unwrap();
+    }
+    stream
+}
+
+async fn echo_async<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S, count: usize) {
+    let mut decoder = SmpTransportDecoder::new();
+    let mut buf = [0; 256];
+    for _ in 0..count {
+        let frame = loop {
+            if let Some(frame) = next_frame(&mut decoder) {
+                break frame;
+            }
+            let len = stream.read(&mut buf).await.unwrap();
+            decoder.push(&buf[..len]);
+        };
+        stream.write_all(&console_lines(&frame)).await.unwrap();
+    }
+}
+
+fn spawn_echo<S: Read + Write + Send + 'static>(stream: S, count: usize) -> JoinHandle<S> {
+    std::thread::spawn(move || echo(stream, count))
+}
+
+fn roundtrip<S: Read + Write>(mut transport: ConsoleTransport<S>) {
+    for frame in [vec![1, 2, 3], vec![0x55; 300]] {
+        transport.send(frame.clone()).unwrap();
+        assert_eq!(transport.receive().unwrap(), frame);
+    }
+}
+
+async fn roundtrip_async<S: AsyncRead + AsyncWrite + Unpin + Send>(
+    mut transport: ConsoleTransportAsync<S>,
+) {
+    for frame in [vec![1, 2, 3], vec![0x55; 300]] {
+        transport.send(frame.clone()).await.unwrap();
+        assert_eq!(transport.receive().await.unwrap(), frame);
+    }
+}
+
+fn socket_path(name: &str) -> std::path::PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("mcumgr-smp-{}-{}.sock", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let device = std::thread::spawn(move || echo(listener.accept().unwrap().0, 2));
+
+    let mut transport = ConsoleTransport::connect_tcp(addr).unwrap();
+    let output = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = output.clone();
+    transport.on_console_output(move |text| log.lock().unwrap().push(text.to_vec()));
+
+    roundtrip(transport);
+    device.join().unwrap();
+    assert_eq!(output.lock().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn tcp_async() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let device =
+        tokio::spawn(async move { echo_async(listener.accept().await.unwrap().0, 2).await });
+
+    roundtrip_async(ConsoleTransportAsync::connect_tcp(addr).await.unwrap()).await;
+    device.await.unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_stream_pair() {
+    let (host, device) = std::os::unix::net::UnixStream::pair().unwrap();
+    let device = spawn_echo(device, 2);
+
+    roundtrip(ConsoleTransport::new(host));
+    device.join().unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn unix_stream_pair_async() {
+    let (host, device) = tokio::net::UnixStream::pair().unwrap();
+    let device = tokio::spawn(echo_async(device, 2));
+
+    roundtrip_async(ConsoleTransportAsync::new(host)).await;
+    device.await.unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn skips_invalid_frame() {
+    let (host, mut device) = std::os::unix::net::UnixStream::pair().unwrap();
+    device.write_all(&invalid_then_valid(&[1, 2, 3])).unwrap();
+
+    let mut transport = ConsoleTransport::new(host);
+    let errors = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let log = errors.clone();
+    transport.on_invalid_frame(move |err| log.lock().unwrap().push(err.to_string()));
+
+    assert_eq!(transport.receive().unwrap(), [1, 2, 3]);
+    assert_eq!(*errors.lock().unwrap(), ["SmpTransport: wrong crc"]);
+}
+
+#[tokio::test]
+async fn skips_invalid_frame_async() {
+    let (host, mut device) = tokio::io::duplex(4096);
+    device
+        .write_all(&invalid_then_valid(&[1, 2, 3]))
+        .await
+        .unwrap();
+
+    let mut transport = ConsoleTransportAsync::new(host);
+    assert_eq!(transport.receive().await.unwrap(), [1, 2, 3]);
+}
+
+#[cfg(unix)]
+#[test]
+fn timeout() {
+    let (host, _device) = std::os::unix::net::UnixStream::pair().unwrap();
+    host.set_read_timeout(Some(Duration::from_millis(50)))
+        .unwrap();
+
+    let mut transport = ConsoleTransport::new(host);
+    assert!(matches!(transport.receive(), Err(Error::Timeout)));
+}
+
+#[cfg(unix)]
+#[test]
+fn unix_connect() {
+    let path = socket_path("unix-connect");
+    let listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+    let device = std::thread::spawn(move || echo(listener.accept().unwrap().0, 2));
+
+    roundtrip(ConsoleTransport::connect_unix(&path).unwrap());
+    device.join().unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn unix_connect_async() {
+    let path = socket_path("unix-connect-async");
+    let listener = tokio::net::UnixListener::bind(&path).unwrap();
+    let device =
+        tokio::spawn(async move { echo_async(listener.accept().await.unwrap().0, 2).await });
+
+    roundtrip_async(ConsoleTransportAsync::connect_unix(&path).await.unwrap()).await;
+    device.await.unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[cfg(all(unix, feature = "transport-serial"))]
+#[test]
+fn pty() {
+    use serialport::SerialPort;
+
+    // the pair sets the terminal to raw mode, keep it open until the test is done
+    let (mut device, terminal) = serialport::TTYPort::pair().unwrap();
+    device.set_timeout(Duration::from_secs(5)).unwrap();
+    let device = spawn_echo(device, 2);
+
+    roundtrip(ConsoleTransport::open_pty(terminal.name().unwrap()).unwrap());
+    device.join().unwrap();
+}
+
+#[cfg(all(unix, feature = "transport-serial-async"))]
+#[tokio::test]
+async fn pty_async_after_timeout() {
+    use tokio_serial::SerialPort;
+
+    let (mut device, terminal) = tokio_serial::SerialStream::pair().unwrap();
+    let mut transport = ConsoleTransportAsync::open_pty(terminal.name().unwrap())
+        .await
+        .unwrap();
+    transport.recv_timeout(Some(Duration::from_millis(50)));
+
+    // the device doesn't answer the first request in time
+    transport.send(vec![1, 2, 3]).await.unwrap();
+    let err = transport.receive().await.unwrap_err();
+    assert!(matches!(err, Error::Timeout));
+
+    // the cancelled read must not block the next request and response
+    let device = tokio::spawn(async move {
+        echo_async(&mut device, 2).await;
+        device
+    });
+    transport.send(vec![4, 5, 6]).await.unwrap();
+    assert_eq!(transport.receive().await.unwrap(), [1, 2, 3]);
+    assert_eq!(transport.receive().await.unwrap(), [4, 5, 6]);
+    device.await.unwrap();
+}
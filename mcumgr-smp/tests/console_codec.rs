@@ -0,0 +1,118 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Tests for the tokio-util codec of the console framing.
+
+#![cfg(feature = "console-codec")]
+
+use bytes::BytesMut;
+use futures::{SinkExt, StreamExt};
+use mcumgr_smp::codec::RawCodec;
+use mcumgr_smp::transport::console::SmpConsoleCodec;
+use mcumgr_smp::{Group, OpCode, SmpFrame};
+use std::sync::{Arc, Mutex};
+use tokio::io::AsyncWriteExt;
+use tokio_util::codec::{Decoder, Encoder, Framed};
+
+fn frame(seq: u8, data: Vec<u8>) -> Vec<u8> {
+    SmpFrame::new(OpCode::ReadResponse, seq, Group::Default, 0, data)
+        .encode::<RawCodec>()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn framed_roundtrip() {
+    let (host, device) = tokio::io::duplex(4096);
+    let mut host = Framed::new(host, SmpConsoleCodec::new());
+    let mut device = Framed::new(device, SmpConsoleCodec::new().with_line_length(32));
+
+    let request = frame(1, vec![0x55; 300]);
+    let response = frame(1, vec![0xa0]);
+
+    host.send(request.clone()).await.unwrap();
+    assert_eq!(device.next().await.unwrap().unwrap(), request);
+    device.send(response.clone()).await.unwrap();
+    assert_eq!(host.next().await.unwrap().unwrap(), response);
+}
+
+#[test]
+fn partial_reads_with_console_output() {
+    let first = frame(1, vec![1; 200]);
+    let second = frame(2, vec![2, 3]);
+
+    let mut encoded = BytesMut::new();
+    let mut codec = SmpConsoleCodec::new().with_line_length(40);
+    codec.encode(&first, &mut encoded).unwrap();
+    encoded.extend_from_slice(b"[00:00:01.000] <inf> app: hello\n");
+    codec.encode(&second, &mut encoded).unwrap();
+    assert!(encoded[..]
+        .split(|&b| b == b'\n')
+        .all(|line| line.len() < 40));
+
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let log = output.clone();
+    let mut codec = SmpConsoleCodec::new()
+        .on_console_output(move |text| log.lock().unwrap().push(text.to_vec()));
+
+    let mut frames = Vec::new();
+    let mut src = BytesMut::new();
+    for chunk in encoded.chunks(7) {
+        src.extend_from_slice(chunk);
+        while let Some(frame) = codec.decode(&mut src).unwrap() {
+            frames.push(frame);
+        }
+    }
+    assert_eq!(frames, [first, second]);
+    assert_eq!(
+        *output.lock().unwrap(),
+        [b"[00:00:01.000] <inf> app: hello".to_vec()]
+    );
+}
+
+#[test]
+fn continues_after_invalid_frame() {
+    let valid = frame(3, vec![4, 5, 6]);
+    let mut codec = SmpConsoleCodec::new();
+
+    let mut src = BytesMut::from(&b"\x06\x09AAAA\n"[..]);
+    codec.encode(&valid, &mut src).unwrap();
+
+    assert!(codec.decode(&mut src).is_err());
+    assert_eq!(codec.decode(&mut src).unwrap(), Some(valid));
+}
+
+/// A frame with a wrong CRC followed by a valid one
+fn invalid_then_valid(valid: &[u8]) -> BytesMut {
+    let mut src = BytesMut::new();
+    SmpConsoleCodec::new().encode(valid, &mut src).unwrap();
+    let mut corrupted = src.clone();
+    // change a base64 character of the payload
+    corrupted[12] = if corrupted[12] == b'A' { b'B' } else { b'A' };
+    corrupted.extend_from_slice(&src);
+    corrupted
+}
+
+#[tokio::test]
+async fn framed_skips_invalid_frame() {
+    let valid = frame(3, vec![4, 5, 6]);
+    let (mut host, device) = tokio::io::duplex(4096);
+    host.write_all(&invalid_then_valid(&valid)).await.unwrap();
+
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let log = errors.clone();
+    let codec = SmpConsoleCodec::new()
+        .on_invalid_frame(move |err| log.lock().unwrap().push(err.to_string()));
+    let mut device = Framed::new(device, codec);
+
+    assert_eq!(device.next().await.unwrap().unwrap(), valid);
+    assert_eq!(*errors.lock().unwrap(), ["SmpTransport: wrong crc"]);
+}
+
+#[tokio::test]
+async fn framed_returns_invalid_frame() {
+    let valid = frame(3, vec![4, 5, 6]);
+    let (mut host, device) = tokio::io::duplex(4096);
+    host.write_all(&invalid_then_valid(&valid)).await.unwrap();
+
+    let mut device = Framed::new(device, SmpConsoleCodec::new());
+    assert!(device.next().await.unwrap().is_err());
+}
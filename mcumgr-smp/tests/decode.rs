@@ -0,0 +1,250 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Property tests for the frame and console framing decoders.
+//! Decoding arbitrary input must return an error instead of panicking.
+//! The allocation-free encoders must produce the same bytes as [SmpFrame::encode].
+
+use mcumgr_smp::codec::RawCodec;
+use mcumgr_smp::transport::smp_framing::{
+    ConsoleInput, SmpTransportDecoder, SmpTransportEncoder, SmpTransportError, MAX_LINE_LENGTH,
+};
+use mcumgr_smp::{Group, OpCode, SmpError, SmpFrame, SmpVersion};
+use proptest::prelude::*;
+
+fn decode_lines(input: &[u8]) -> Option<Vec<u8>> {
+    let mut decoder = SmpTransportDecoder::new();
+    for line in input.split_inclusive(|&b| b == b'\n') {
+        if decoder.input_line(line).ok()? {
+            return decoder.into_frame_payload().ok();
+        }
+    }
+    None
+}
+
+proptest! {
+    #[test]
+    fn frame_decode_does_not_panic(buf in proptest::collection::vec(any::<u8>(), 0..64)) {
+        let _ = SmpFrame::<Vec<u8>>::decode::<RawCodec>(&buf);
+    }
+
+    #[test]
+    fn frame_roundtrip(
+        op in 0u8..4,
+        v2 in any::<bool>(),
+        flags in any::<u8>(),
+        group in any::<u16>(),
+        sequence in any::<u8>(),
+        command in any::<u8>(),
+        payload in proptest::collection::vec(any::<u8>(), 0..512),
+    ) {
+        let mut frame = SmpFrame::new(
+            OpCode::try_from(op).unwrap(),
+            sequence,
+            Group::from(group),
+            command,
+            payload,
+        );
+        frame.flags = flags;
+        frame.version = if v2 { SmpVersion::V2 } else { SmpVersion::V1 };
+
+        let bytes = frame.encode::<RawCodec>().unwrap();
+        let decoded = SmpFrame::<Vec<u8>>::decode::<RawCodec>(&bytes).unwrap();
+
+        prop_assert_eq!(u8::from(decoded.operation), op);
+        prop_assert_eq!(decoded.version, frame.version);
+        prop_assert_eq!(decoded.flags, flags);
+        prop_assert_eq!(u16::from(decoded.group), group);
+        prop_assert_eq!(decoded.sequence, sequence);
+        prop_assert_eq!(decoded.command, command);
+        prop_assert_eq!(decoded.data, frame.data);
+    }
+
+    #[test]
+    fn encode_into_matches_encode(
+        sequence in any::<u8>(),
+        payload in proptest::collection::vec(any::<u8>(), 0..512),
+        spare in 0usize..16,
+    ) {
+        let frame = SmpFrame::new(OpCode::WriteRequest, sequence, Group::Default, 0, payload);
+        let expected = frame.encode::<RawCodec>().unwrap();
+
+        let mut buf = vec![0; expected.len() + spare];
+        let len = frame.encode_into::<RawCodec>(&mut buf).unwrap();
+        prop_assert_eq!(&buf[..len], &expected[..]);
+
+        #[cfg(feature = "std")]
+        {
+            let mut written = Vec::new();
+            let len = frame.encode_to::<RawCodec>(&mut written).unwrap();
+            prop_assert_eq!(len, expected.len());
+            prop_assert_eq!(written, expected.clone());
+        }
+
+        let mut short = vec![0; expected.len() - 1];
+        prop_assert!(matches!(
+            frame.encode_into::<RawCodec>(&mut short),
+            Err(SmpError::BufferTooSmall)
+        ));
+    }
+
+    #[test]
+    fn console_decode_does_not_panic(lines in proptest::collection::vec(
+        prop_oneof![
+            proptest::collection::vec(any::<u8>(), 0..8),
+            "[\\x06\\x04][\\x09\\x14][A-Za-z0-9+/=]{0,16}\\n?".prop_map(String::into_bytes),
+        ],
+        0..8,
+    )) {
+        let _ = decode_lines(&lines.concat());
+    }
+
+    #[test]
+    fn console_roundtrip(
+        payload in proptest::collection::vec(any::<u8>(), 0..1024),
+        line_length in 0usize..300,
+    ) {
+        let mut encoder = SmpTransportEncoder::new(&payload).with_line_length(line_length);
+        let mut encoded = Vec::new();
+        let mut buf = [0; 300];
+        while !encoder.is_complete() {
+            let len = encoder.write_line(&mut buf).unwrap();
+            prop_assert!(len <= encoder.line_length());
+            encoded.extend_from_slice(&buf[..len]);
+        }
+
+        prop_assert_eq!(decode_lines(&encoded), Some(payload));
+    }
+
+    #[test]
+    fn console_skips_log_output(
+        payload in proptest::collection::vec(any::<u8>(), 1..512),
+        logs in proptest::collection::vec("[ -~]{1,40}", 0..6),
+        crlf in any::<bool>(),
+        chunk in 1usize..64,
+    ) {
+        let mut frame_lines = Vec::new();
+        let mut encoder = SmpTransportEncoder::new(&payload);
+        let mut buf = [0; 127];
+        while !encoder.is_complete() {
+            let len = encoder.write_line(&mut buf).unwrap();
+            frame_lines.push(buf[..len].to_vec());
+        }
+        // spread the log lines over the frame, before, between and after its lines
+        let mut logs_iter = logs.iter().enumerate().peekable();
+        let mut lines = Vec::new();
+        for pos in 0..=frame_lines.len() {
+            while let Some((_, log)) =
+                logs_iter.next_if(|(i, _)| i * (frame_lines.len() + 1) / logs.len() <= pos)
+            {
+                lines.push(format!("{}\n", log).into_bytes());
+            }
+            if let Some(line) = frame_lines.get(pos) {
+                lines.push(line.clone());
+            }
+        }
+        if crlf {
+            for line in &mut lines {
+                line.insert(line.len() - 1, b'\r');
+            }
+        }
+
+        let mut decoder = SmpTransportDecoder::new();
+        let mut frames = Vec::new();
+        let mut text = Vec::new();
+        for data in lines.concat().chunks(chunk) {
+            decoder.push(data);
+            while let Some(input) = decoder.next_input() {
+                match input.unwrap() {
+                    ConsoleInput::Frame(frame) => frames.push(frame),
+                    ConsoleInput::Text(line) => text.push(String::from_utf8(line).unwrap()),
+                }
+            }
+        }
+
+        prop_assert_eq!(frames, vec![payload]);
+        prop_assert_eq!(text, logs);
+    }
+
+    #[test]
+    fn console_drops_long_lines(
+        noise in proptest::collection::vec(
+            any::<u8>().prop_filter("no newline or frame start", |b| ![b'\n', 0x04, 0x06].contains(b)),
+            0..4 * MAX_LINE_LENGTH,
+        ),
+        chunk in 1usize..256,
+    ) {
+        let mut frame_line = [0; 127];
+        let len = SmpTransportEncoder::new(&[1, 2, 3]).write_line(&mut frame_line).unwrap();
+
+        // binary data without newlines, then a frame
+        let mut data = noise.clone();
+        data.push(b'\n');
+        data.extend_from_slice(&frame_line[..len]);
+
+        let mut decoder = SmpTransportDecoder::new();
+        let mut frames = Vec::new();
+        let mut too_long = 0;
+        for data in data.chunks(chunk) {
+            decoder.push(data);
+            while let Some(input) = decoder.next_input() {
+                match input {
+                    Ok(ConsoleInput::Frame(frame)) => frames.push(frame),
+                    Ok(ConsoleInput::Text(line)) => prop_assert!(line.len() < MAX_LINE_LENGTH),
+                    Err(SmpTransportError::LineTooLong(MAX_LINE_LENGTH)) => too_long += 1,
+                    Err(err) => prop_assert!(false, "unexpected error {}", err),
+                }
+            }
+        }
+
+        prop_assert_eq!(frames, vec![vec![1, 2, 3]]);
+        prop_assert_eq!(too_long, usize::from(noise.len() + 1 > MAX_LINE_LENGTH));
+    }
+}
+
+#[test]
+fn console_text_before_frame_start() {
+    let mut buf = [0; 127];
+    let len = SmpTransportEncoder::new(&[1, 2, 3])
+        .write_line(&mut buf)
+        .unwrap();
+
+    let mut decoder = SmpTransportDecoder::new();
+    decoder.push(b"[00:00:01.000] <inf> log: ");
+    decoder.push(&buf[..len]);
+    assert_eq!(
+        decoder.next_input().unwrap().unwrap(),
+        ConsoleInput::Text(b"[00:00:01.000] <inf> log: ".to_vec())
+    );
+    assert_eq!(
+        decoder.next_input().unwrap().unwrap(),
+        ConsoleInput::Frame(vec![1, 2, 3])
+    );
+    assert!(decoder.next_input().is_none());
+}
+
+#[test]
+fn console_frame_start_drops_partial_frame() {
+    let mut buf = [0; 127];
+    let large = vec![0x55; 300];
+    let len = SmpTransportEncoder::new(&large)
+        .write_line(&mut buf)
+        .unwrap();
+
+    let mut decoder = SmpTransportDecoder::new();
+    decoder.push(&buf[..len]);
+    assert!(decoder.next_input().is_none());
+
+    let frame = SmpFrame::new(OpCode::ReadResponse, 1, Group::Default, 0, vec![0xa0])
+        .encode::<RawCodec>()
+        .unwrap();
+    assert_eq!(frame.len(), 9);
+    let len = SmpTransportEncoder::new(&frame)
+        .write_line(&mut buf)
+        .unwrap();
+    decoder.push(&buf[..len]);
+    assert_eq!(
+        decoder.next_input().unwrap().unwrap(),
+        ConsoleInput::Frame(frame)
+    );
+    assert!(decoder.next_input().is_none());
+}
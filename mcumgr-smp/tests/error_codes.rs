@@ -0,0 +1,193 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Table-driven tests for the error code enums.
+
+use mcumgr_smp::error_codes::{
+    EnumMgmtError, FsMgmtError, GroupError, ImageMgmtError, OsMgmtError, ReturnCode,
+    SettingsMgmtError, ShellMgmtError, StatsMgmtError,
+};
+use mcumgr_smp::Group;
+
+/// Checks the known codes of `$error` against a table of `code => (variant, text)`,
+/// that all codes in `$range` are known and that the `unknown` codes are rejected
+macro_rules! check_codes {
+    (
+        $error:ident, $range:expr,
+        known: [$($code:literal => ($variant:ident, $text:literal),)*],
+        unknown: [$($unknown:expr),*],
+    ) => {{
+        $(
+            let error = $error::try_from($code).unwrap();
+            assert_eq!(error, $error::$variant);
+            assert_eq!(i32::from(error), $code);
+            assert_eq!(error.to_string(), $text);
+        )*
+        for code in $range {
+            let error = $error::try_from(code)
+                .unwrap_or_else(|_| panic!("{} {} unknown", stringify!($error), code));
+            assert_eq!(i32::from(error), code);
+            assert!(!error.to_string().is_empty());
+        }
+        $(
+            assert_eq!($error::try_from($unknown), Err($unknown));
+        )*
+    }};
+}
+
+#[test]
+fn return_codes() {
+    check_codes!(
+        ReturnCode, 0..=13,
+        known: [
+            0 => (Ok, "no error"),
+            8 => (NotSupported, "command not supported"),
+            13 => (UnsupportedTooNew, "protocol version too new"),
+            256 => (UserDefined, "user defined error"),
+        ],
+        unknown: [-1, 14, 255, 257, i32::MIN, i32::MAX],
+    );
+}
+
+#[test]
+fn os_errors() {
+    check_codes!(
+        OsMgmtError, 0..=6,
+        known: [
+            0 => (Ok, "no error"),
+            2 => (InvalidFormat, "invalid format"),
+            6 => (QueryResponseValueNotValid, "query response value not valid"),
+        ],
+        unknown: [-1, 7, 256],
+    );
+}
+
+#[test]
+fn image_errors() {
+    check_codes!(
+        ImageMgmtError, 0..=34,
+        known: [
+            0 => (Ok, "no error"),
+            3 => (NoImage, "no image in slot"),
+            34 => (ActiveSlotNotKnown, "active slot not known"),
+        ],
+        unknown: [-1, 35],
+    );
+}
+
+#[test]
+fn fs_errors() {
+    check_codes!(
+        FsMgmtError, 0..=16,
+        known: [
+            0 => (Ok, "no error"),
+            3 => (FileNotFound, "file not found"),
+            16 => (FileEmpty, "file is empty"),
+        ],
+        unknown: [-1, 17],
+    );
+}
+
+#[test]
+fn stats_errors() {
+    check_codes!(
+        StatsMgmtError, 0..=5,
+        known: [
+            0 => (Ok, "no error"),
+            2 => (InvalidGroup, "statistics group not found"),
+            5 => (WalkAborted, "walk through statistics aborted"),
+        ],
+        unknown: [-1, 6],
+    );
+}
+
+#[test]
+fn settings_errors() {
+    check_codes!(
+        SettingsMgmtError, 0..=7,
+        known: [
+            0 => (Ok, "no error"),
+            3 => (KeyNotFound, "key not found"),
+            7 => (DeleteNotSupported, "delete not supported"),
+        ],
+        unknown: [-1, 8],
+    );
+}
+
+#[test]
+fn shell_errors() {
+    check_codes!(
+        ShellMgmtError, 0..=3,
+        known: [
+            0 => (Ok, "no error"),
+            3 => (EmptyCommand, "empty command"),
+        ],
+        unknown: [-1, 4],
+    );
+}
+
+#[test]
+fn enum_errors() {
+    check_codes!(
+        EnumMgmtError, 0..=4,
+        known: [
+            0 => (Ok, "no error"),
+            4 => (IndexTooLarge, "index too large"),
+        ],
+        unknown: [-1, 5],
+    );
+}
+
+#[test]
+fn group_errors() {
+    let table = [
+        (
+            Group::Default,
+            2,
+            Some(GroupError::Os(OsMgmtError::InvalidFormat)),
+        ),
+        (
+            Group::ApplicationManagement,
+            3,
+            Some(GroupError::Image(ImageMgmtError::NoImage)),
+        ),
+        (
+            Group::Statistics,
+            2,
+            Some(GroupError::Stats(StatsMgmtError::InvalidGroup)),
+        ),
+        (
+            Group::FileManagement,
+            3,
+            Some(GroupError::Fs(FsMgmtError::FileNotFound)),
+        ),
+        (
+            Group::Settings,
+            3,
+            Some(GroupError::Settings(SettingsMgmtError::KeyNotFound)),
+        ),
+        (
+            Group::ShellManagement,
+            3,
+            Some(GroupError::Shell(ShellMgmtError::EmptyCommand)),
+        ),
+        (
+            Group::Enumeration,
+            4,
+            Some(GroupError::Enumeration(EnumMgmtError::IndexTooLarge)),
+        ),
+        // unknown code, and a group without defined error codes
+        (Group::Default, 7, None),
+        (Group::Custom(64), 1, None),
+    ];
+    for (group, rc, expected) in table {
+        let error = GroupError::new(group, rc);
+        assert_eq!(error, expected, "group {:?} rc {}", group, rc);
+        if let Some(error) = error {
+            assert_eq!((error.group(), error.rc()), (group, rc));
+        }
+    }
+
+    let error = GroupError::from(ShellMgmtError::EmptyCommand);
+    assert_eq!(error.to_string(), "empty command");
+    assert_eq!(error.return_code(), ReturnCode::InvalidValue);
+}
@@ -0,0 +1,77 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Tests for splitting and reassembling frames on transports with a small MTU.
+
+use mcumgr_smp::codec::RawCodec;
+use mcumgr_smp::transport::fragment::{fragments, FrameAssembler};
+use mcumgr_smp::{Group, OpCode, SmpFrame};
+use proptest::prelude::*;
+
+fn frame(seq: u8, data: Vec<u8>) -> Vec<u8> {
+    SmpFrame::new(OpCode::ReadResponse, seq, Group::Default, 0, data)
+        .encode::<RawCodec>()
+        .unwrap()
+}
+
+proptest! {
+    #[test]
+    fn reassemble_chunks(
+        data in proptest::collection::vec(any::<u8>(), 0..600),
+        chunk in 1usize..100,
+    ) {
+        let frame = frame(1, data);
+        let mut assembler = FrameAssembler::new();
+
+        let chunks: Vec<_> = frame.chunks(chunk).collect();
+        let (last, rest) = chunks.split_last().unwrap();
+        for c in rest {
+            assembler.push(c);
+            prop_assert_eq!(assembler.next_frame(), None);
+        }
+        assembler.push(last);
+        prop_assert_eq!(assembler.next_frame(), Some(frame));
+        prop_assert_eq!(assembler.pending(), 0);
+    }
+
+    #[test]
+    fn split_and_reassemble(
+        data in proptest::collection::vec(any::<u8>(), 0..600),
+        max_len in 0usize..300,
+    ) {
+        let frame = frame(1, data);
+        let mut assembler = FrameAssembler::new();
+
+        for chunk in fragments(&frame, max_len) {
+            prop_assert!(chunk.len() <= max_len.max(1));
+            assembler.push(chunk);
+        }
+        prop_assert_eq!(assembler.next_frame(), Some(frame));
+    }
+}
+
+#[test]
+fn frames_in_one_packet() {
+    let first = frame(1, vec![1, 2, 3]);
+    let second = frame(2, vec![4, 5]);
+    let mut assembler = FrameAssembler::new();
+
+    let mut data = first.clone();
+    data.extend_from_slice(&second[..4]);
+    assembler.push(&data);
+    assert_eq!(assembler.next_frame(), Some(first));
+    assert_eq!(assembler.next_frame(), None);
+
+    assembler.push(&second[4..]);
+    assert_eq!(assembler.next_frame(), Some(second));
+}
+
+#[test]
+fn reset_drops_partial_frame() {
+    let frame = frame(1, vec![1, 2, 3]);
+    let mut assembler = FrameAssembler::new();
+
+    assembler.push(&[0xff; 10]);
+    assembler.reset();
+    assembler.push(&frame);
+    assert_eq!(assembler.next_frame(), Some(frame));
+}
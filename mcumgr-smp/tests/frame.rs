@@ -0,0 +1,164 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Tests for encoding and decoding frame headers.
+
+use mcumgr_smp::codec::RawCodec;
+use mcumgr_smp::{Group, OpCode, SmpError, SmpFrame, SmpHeader, SmpVersion};
+
+const OPERATIONS: [OpCode; 4] = [
+    OpCode::ReadRequest,
+    OpCode::ReadResponse,
+    OpCode::WriteRequest,
+    OpCode::WriteResponse,
+];
+
+fn frame(operation: OpCode, version: SmpVersion) -> SmpFrame<Vec<u8>> {
+    let mut frame = SmpFrame::new(operation, 42, Group::ApplicationManagement, 1, vec![0xa0]);
+    frame.version = version;
+    frame
+}
+
+#[test]
+fn version_defaults_to_v2() {
+    assert_eq!(SmpVersion::default(), SmpVersion::V2);
+    let bytes = frame(OpCode::ReadRequest, SmpVersion::default())
+        .encode::<RawCodec>()
+        .unwrap();
+    assert_eq!(bytes[0], 0x08);
+}
+
+#[test]
+fn version_bits_roundtrip() {
+    for operation in OPERATIONS {
+        for (version, bits) in [(SmpVersion::V1, 0x00), (SmpVersion::V2, 0x08)] {
+            let bytes = frame(operation, version).encode::<RawCodec>().unwrap();
+            assert_eq!(bytes[0], u8::from(operation) | bits);
+
+            let header = SmpHeader::parse(&bytes).unwrap();
+            assert_eq!((header.operation(), header.version()), (operation, version));
+            let decoded = SmpFrame::<Vec<u8>>::decode::<RawCodec>(&bytes).unwrap();
+            assert_eq!((decoded.operation, decoded.version), (operation, version));
+        }
+    }
+}
+
+#[test]
+fn unsupported_version() {
+    let mut bytes = frame(OpCode::ReadRequest, SmpVersion::V2)
+        .encode::<RawCodec>()
+        .unwrap();
+    for version in [2, 3] {
+        bytes[0] = (bytes[0] & !0x18) | (version << 3);
+        assert!(matches!(
+            SmpHeader::parse(&bytes),
+            Err(SmpError::UnsupportedVersion(v)) if v == version
+        ));
+        assert!(matches!(
+            SmpFrame::<Vec<u8>>::decode::<RawCodec>(&bytes),
+            Err(SmpError::UnsupportedVersion(v)) if v == version
+        ));
+    }
+}
+
+#[test]
+fn header_short_input() {
+    let bytes = frame(OpCode::ReadRequest, SmpVersion::V2)
+        .encode::<RawCodec>()
+        .unwrap();
+    for len in 0..SmpHeader::LEN {
+        assert!(matches!(
+            SmpHeader::parse(&bytes[..len]),
+            Err(SmpError::InvalidFrame)
+        ));
+    }
+}
+
+#[test]
+fn header_length_field() {
+    let mut bytes = SmpFrame::new(OpCode::ReadResponse, 1, Group::Default, 0, vec![1, 2, 3])
+        .encode::<RawCodec>()
+        .unwrap();
+
+    // the payload is shorter than the length field
+    assert!(matches!(
+        SmpHeader::parse(&bytes[..10]),
+        Err(SmpError::InvalidFrame)
+    ));
+    bytes[2..4].copy_from_slice(&u16::MAX.to_be_bytes());
+    assert!(matches!(
+        SmpHeader::parse(&bytes),
+        Err(SmpError::InvalidFrame)
+    ));
+
+    // trailing bytes after the payload are not part of the frame
+    bytes[2..4].copy_from_slice(&1u16.to_be_bytes());
+    let header = SmpHeader::parse(&bytes).unwrap();
+    assert_eq!(header.length(), 1);
+    assert_eq!(header.payload(), [1]);
+    assert_eq!(header.frame(), &bytes[..9]);
+}
+
+#[test]
+fn header_borrows_payload() {
+    let bytes = SmpFrame::new(OpCode::WriteRequest, 7, Group::Custom(64), 3, vec![4, 5])
+        .encode::<RawCodec>()
+        .unwrap();
+    let header = SmpHeader::parse(&bytes).unwrap();
+
+    assert_eq!(
+        (
+            header.operation(),
+            header.flags(),
+            header.group(),
+            header.sequence(),
+            header.command()
+        ),
+        (OpCode::WriteRequest, 0, Group::Custom(64), 7, 3)
+    );
+    // the payload and frame point into the parsed buffer
+    assert_eq!(header.payload(), [4, 5]);
+    assert!(std::ptr::eq(header.payload(), &bytes[8..]));
+    assert!(std::ptr::eq(header.frame(), &bytes[..]));
+
+    // the slices outlive the header
+    let payload = SmpHeader::parse(&bytes).unwrap().payload();
+    assert_eq!(payload, [4, 5]);
+}
+
+#[test]
+fn command_ids() {
+    use mcumgr_smp::os_management::{
+        EchoRequest, GetInfoRequest, McumgrParamsRequest, OsManagementCommand, ResetRequest,
+    };
+    use mcumgr_smp::shell_management::{ShellCommand, ShellManagementCommand};
+    use mcumgr_smp::stats_management::{
+        StatsListRequest, StatsManagementCommand, StatsReadRequest,
+    };
+    use mcumgr_smp::SmpRequest;
+
+    assert_eq!(
+        [
+            EchoRequest::COMMAND,
+            ResetRequest::COMMAND,
+            McumgrParamsRequest::COMMAND,
+            GetInfoRequest::COMMAND,
+            StatsReadRequest::COMMAND,
+            StatsListRequest::COMMAND,
+            ShellCommand::COMMAND,
+        ],
+        [0, 5, 6, 7, 0, 1, 0]
+    );
+    for id in 0..=u8::MAX {
+        assert_eq!(u8::from(OsManagementCommand::from(id)), id);
+        assert_eq!(u8::from(StatsManagementCommand::from(id)), id);
+        assert_eq!(u8::from(ShellManagementCommand::from(id)), id);
+    }
+    assert!(matches!(
+        OsManagementCommand::from(7),
+        OsManagementCommand::Info
+    ));
+    assert!(matches!(
+        OsManagementCommand::from(9),
+        OsManagementCommand::Unknown(9)
+    ));
+}
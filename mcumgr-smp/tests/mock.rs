@@ -0,0 +1,182 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Tests for the scripted [MockTransport].
+
+#![cfg(all(
+    feature = "transport-mock",
+    feature = "payload-cbor",
+    feature = "async"
+))]
+
+use mcumgr_smp::error_codes::OsMgmtError;
+use mcumgr_smp::os_management::{EchoRequest, EchoResponse, ResetRequest, ResetResponse};
+use mcumgr_smp::response::ResponseError;
+use mcumgr_smp::transport::error::Error;
+use mcumgr_smp::transport::mock::{Expectation, MockTransport};
+use mcumgr_smp::transport::smp::{CborSmpTransport, CborSmpTransportAsync, SmpTransport};
+use mcumgr_smp::{Group, OpCode};
+use serde::{Serialize, Serializer};
+use std::time::{Duration, Instant};
+
+fn echo_expectation(text: &'static str) -> Expectation {
+    Expectation::request::<EchoRequest>()
+        .payload(move |req: &EchoRequest| req.d == text)
+        .respond(EchoResponse { r: text.into() })
+}
+
+fn echo(transport: &mut CborSmpTransport, text: &str) -> Result<EchoResponse, Error> {
+    transport.request(EchoRequest { d: text.into() })
+}
+
+#[test]
+fn scripted_conversation() {
+    let mock = MockTransport::new();
+    mock.expect(echo_expectation("one"))
+        .expect(Expectation::request::<ResetRequest>().respond(ResetResponse {}));
+
+    let mut transport = CborSmpTransport::new(Box::new(mock.clone()));
+    assert_eq!(echo(&mut transport, "one").unwrap().r, "one");
+    transport.request(ResetRequest { force: 0 }).unwrap();
+
+    assert_eq!(mock.sent().len(), 2);
+    mock.verify();
+}
+
+#[test]
+#[should_panic(expected = "frame 1: expected command 0, got 5")]
+fn out_of_order_frames() {
+    let mock = MockTransport::new();
+    mock.expect(echo_expectation("one"))
+        .expect(Expectation::request::<ResetRequest>().respond(ResetResponse {}));
+
+    let mut transport = CborSmpTransport::new(Box::new(mock.clone()));
+    let err = transport.request(ResetRequest { force: 0 }).unwrap_err();
+    assert!(matches!(err, Error::Mock(_)));
+
+    mock.verify();
+}
+
+#[test]
+#[should_panic(expected = "frame 2: no more frames expected")]
+fn unexpected_frame() {
+    let mock = MockTransport::new();
+    mock.expect(echo_expectation("one"));
+
+    let mut transport = CborSmpTransport::new(Box::new(mock.clone()));
+    echo(&mut transport, "one").unwrap();
+    assert!(matches!(echo(&mut transport, "two"), Err(Error::Mock(_))));
+
+    mock.verify();
+}
+
+#[test]
+fn matcher_failures() {
+    let mock = MockTransport::new();
+    mock.expect(echo_expectation("one"))
+        .expect(Expectation::frame().group(Group::ApplicationManagement))
+        .expect(Expectation::frame().operation(OpCode::ReadRequest))
+        .expect(Expectation::frame().payload_bytes([0xa0]));
+
+    let mut transport = CborSmpTransport::new(Box::new(mock.clone()));
+    for _ in 0..4 {
+        assert!(matches!(echo(&mut transport, "two"), Err(Error::Mock(_))));
+    }
+
+    let failures = std::panic::catch_unwind(|| mock.verify())
+        .unwrap_err()
+        .downcast::<String>()
+        .unwrap();
+    assert!(failures.contains("frame 1: unexpected payload"));
+    assert!(failures.contains("frame 2: expected group ApplicationManagement, got Default"));
+    assert!(failures.contains("frame 3: expected operation ReadRequest, got WriteRequest"));
+    assert!(failures.contains("frame 4: unexpected payload [a1, 61, 64, 63, 74, 77, 6f]"));
+}
+
+#[test]
+#[should_panic(expected = "1 expected frame(s) not sent")]
+fn verify_leftover_expectations() {
+    let mock = MockTransport::new();
+    mock.expect(echo_expectation("one"))
+        .expect(echo_expectation("two"));
+
+    let mut transport = CborSmpTransport::new(Box::new(mock.clone()));
+    echo(&mut transport, "one").unwrap();
+
+    mock.verify();
+}
+
+#[test]
+fn injected_errors() {
+    let mock = MockTransport::new();
+    mock.expect(
+        Expectation::request::<EchoRequest>()
+            .fail(Error::Timeout)
+            .respond_frame([0x01]),
+    )
+    .expect(Expectation::request::<EchoRequest>().respond_error(OsMgmtError::InvalidFormat))
+    .expect(Expectation::request::<EchoRequest>());
+
+    let mut mock_transport = mock.clone();
+    mock_transport
+        .send(
+            mcumgr_smp::os_management::echo(0, "".into())
+                .encode_with_cbor()
+                .unwrap(),
+        )
+        .unwrap();
+    assert!(matches!(mock_transport.receive(), Err(Error::Timeout)));
+    assert_eq!(mock_transport.receive().unwrap(), [0x01]);
+
+    let mut transport = CborSmpTransport::new(Box::new(mock.clone()));
+    let err = echo(&mut transport, "one").unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Device(ResponseError::Group { group: 0, rc: 2 })
+    ));
+
+    // no reply scripted, as if the device didn't answer
+    let err = echo(&mut transport, "two").unwrap_err();
+    assert!(matches!(err, Error::Timeout));
+
+    mock.verify();
+}
+
+/// A payload that fails to encode
+struct Unencodable;
+
+impl Serialize for Unencodable {
+    fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("not encodable"))
+    }
+}
+
+#[test]
+fn unencodable_response() {
+    let mock = MockTransport::new();
+    mock.expect(Expectation::request::<EchoRequest>().respond(Unencodable));
+
+    let mut transport = CborSmpTransport::new(Box::new(mock.clone()));
+    let err = echo(&mut transport, "one").unwrap_err();
+    assert!(matches!(err, Error::Mock(msg) if msg.contains("not encodable")));
+}
+
+#[tokio::test]
+async fn delays() {
+    let mock = MockTransport::new();
+    mock.expect(
+        Expectation::request::<EchoRequest>()
+            .delay(Duration::from_millis(50))
+            .respond(EchoResponse { r: "one".into() }),
+    );
+
+    let mut transport = CborSmpTransportAsync::new(Box::new(mock.clone()));
+    let start = Instant::now();
+    let response = transport
+        .request(EchoRequest { d: "one".into() })
+        .await
+        .unwrap();
+    assert_eq!(response.r, "one");
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    mock.verify();
+}
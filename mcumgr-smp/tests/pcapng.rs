@@ -0,0 +1,122 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Tests for the block layout of [PcapngWriter] captures.
+
+#![cfg(feature = "std")]
+
+use mcumgr_smp::transport::pcapng::{CaptureLink, Direction, PcapngWriter, CAPTURE_PORT};
+
+fn u16_le(buf: &[u8]) -> u16 {
+    u16::from_le_bytes(buf[..2].try_into().unwrap())
+}
+
+fn u32_le(buf: &[u8]) -> u32 {
+    u32::from_le_bytes(buf[..4].try_into().unwrap())
+}
+
+/// Split a capture into its blocks, checking the lengths and alignment of each
+fn blocks(mut capture: &[u8]) -> Vec<(u32, &[u8])> {
+    let mut blocks = Vec::new();
+    while !capture.is_empty() {
+        let len = u32_le(&capture[4..]) as usize;
+        assert_eq!(len % 4, 0, "block length {} not padded", len);
+        assert_eq!(u32_le(&capture[len - 4..]) as usize, len, "trailing length");
+        blocks.push((u32_le(capture), &capture[8..len - 4]));
+        capture = &capture[len..];
+    }
+    blocks
+}
+
+/// The options of a block, as (code, value) pairs up to the end option
+fn options(mut buf: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut options = Vec::new();
+    loop {
+        let (code, len) = (u16_le(buf), u16_le(&buf[2..]) as usize);
+        if code == 0 {
+            assert_eq!((len, buf.len()), (0, 4), "end of options");
+            return options;
+        }
+        options.push((code, &buf[4..4 + len]));
+        let padded = 4 + len.next_multiple_of(4);
+        assert!(
+            buf[4 + len..padded].iter().all(|b| *b == 0),
+            "option padding"
+        );
+        buf = &buf[padded..];
+    }
+}
+
+#[test]
+fn section_and_interface_headers() {
+    let mut capture = Vec::new();
+    PcapngWriter::new(&mut capture, CaptureLink::Udp).unwrap();
+
+    let blocks = blocks(&capture);
+    assert_eq!(blocks.len(), 2);
+
+    let (block_type, shb) = blocks[0];
+    assert_eq!(block_type, 0x0A0D_0D0A);
+    assert_eq!(capture[4..8], 32u32.to_le_bytes());
+    assert_eq!(u32_le(shb), 0x1A2B_3C4D);
+    assert_eq!((u16_le(&shb[4..]), u16_le(&shb[6..])), (1, 0));
+    assert_eq!(shb[8..16], [0xff; 8]);
+    assert!(options(&shb[16..]).is_empty());
+
+    let (block_type, idb) = blocks[1];
+    assert_eq!(block_type, 1);
+    assert_eq!(capture[36..40], 72u32.to_le_bytes());
+    assert_eq!(
+        (u16_le(idb), u16_le(&idb[2..]), u32_le(&idb[4..])),
+        (101, 0, 0)
+    );
+    assert_eq!(
+        options(&idb[8..]),
+        [
+            (2, b"smp-udp".as_slice()),
+            (3, b"SMP frames wrapped in IPv4/UDP".as_slice())
+        ]
+    );
+}
+
+#[test]
+fn enhanced_packet_blocks() {
+    let mut capture = Vec::new();
+    let mut writer = PcapngWriter::new(&mut capture, CaptureLink::Serial).unwrap();
+    let frame = [0x0a, 0x00, 0x00, 0x00, 0x00];
+    writer.write_frame(Direction::Outbound, &frame).unwrap();
+    writer.write_frame(Direction::Inbound, &frame).unwrap();
+
+    let blocks = blocks(&capture);
+    assert_eq!(blocks.len(), 4);
+    for (i, (block_type, epb)) in blocks[2..].iter().enumerate() {
+        assert_eq!(*block_type, 6);
+        // 20 byte header, 33 byte packet padded to 36, flags and end option
+        assert_eq!(epb.len() + 12, 80);
+        assert_eq!(u32_le(epb), 0, "interface id");
+        assert_eq!((u32_le(&epb[12..]), u32_le(&epb[16..])), (33, 33));
+
+        let packet = &epb[20..53];
+        assert_eq!(epb[53..56], [0, 0, 0]);
+        let (src, dst, flags) = if i == 0 {
+            ([127, 0, 0, 1], [127, 0, 0, 2], 0b10u32)
+        } else {
+            ([127, 0, 0, 2], [127, 0, 0, 1], 0b01u32)
+        };
+        assert_eq!(options(&epb[56..]), [(2, flags.to_le_bytes().as_slice())]);
+
+        // known IPv4 header, the checksum doesn't depend on the direction
+        assert_eq!(
+            packet[..12],
+            [0x45, 0, 0, 33, 0, 0, 0x40, 0, 64, 17, 0x3c, 0xc9]
+        );
+        assert_eq!(packet[12..16], src);
+        assert_eq!(packet[16..20], dst);
+
+        let port = CAPTURE_PORT.to_be_bytes();
+        assert_eq!(
+            packet[20..28],
+            [port[0], port[1], port[0], port[1], 0, 13, 0, 0]
+        );
+        assert_eq!(packet[28..], frame);
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Tests for recording and replaying sessions with [RecordingTransport] and [ReplayTransport].
+
+#![cfg(feature = "std")]
+
+use mcumgr_smp::codec::RawCodec;
+use mcumgr_smp::transport::error::Error;
+use mcumgr_smp::transport::record::{
+    read_recording, Event, Record, RecordingTransport, ReplayTransport,
+};
+use mcumgr_smp::transport::smp::SmpTransport;
+use mcumgr_smp::{Group, OpCode, SmpError, SmpFrame, SmpHeader};
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A recording file kept in memory
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Returns the scripted results on receive
+struct Scripted(VecDeque<Result<Vec<u8>, Error>>);
+
+impl SmpTransport for Scripted {
+    fn send(&mut self, _frame: Vec<u8>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Result<Vec<u8>, Error> {
+        self.0.pop_front().unwrap()
+    }
+}
+
+fn frame(operation: OpCode, sequence: u8, payload: u8) -> Vec<u8> {
+    SmpFrame::new(operation, sequence, Group::Default, 0, vec![payload])
+        .encode::<RawCodec>()
+        .unwrap()
+}
+
+fn record(secs: f64, event: Event) -> Record {
+    Record {
+        time: Duration::from_secs_f64(secs),
+        event,
+    }
+}
+
+#[test]
+fn recording_roundtrip() {
+    let request = frame(OpCode::ReadRequest, 1, 1);
+    let response = frame(OpCode::ReadResponse, 1, 2);
+    let inner = Scripted(VecDeque::from([
+        Ok(response.clone()),
+        Err(Error::Timeout),
+        Err(io::Error::new(io::ErrorKind::TimedOut, "no answer\nfrom device").into()),
+        Err(Error::Smp(SmpError::InvalidFrame)),
+    ]));
+
+    let buffer = Buffer::default();
+    let mut transport = RecordingTransport::new(inner, buffer.clone()).unwrap();
+    transport.send(request.clone()).unwrap();
+    assert_eq!(transport.receive().unwrap(), response);
+    assert!(matches!(transport.receive(), Err(Error::Timeout)));
+    assert!(transport.receive().is_err());
+    assert!(transport.receive().is_err());
+
+    let recording = buffer.0.lock().unwrap().clone();
+    let records = read_recording(recording.as_slice()).unwrap();
+    let events: Vec<Event> = records.into_iter().map(|r| r.event).collect();
+    assert_eq!(
+        events,
+        [
+            Event::Sent(request),
+            Event::Received(response),
+            Event::Timeout,
+            // messages are kept on a single line
+            Event::Error(io::ErrorKind::TimedOut, "Io: no answer from device".into()),
+            Event::Error(
+                io::ErrorKind::Other,
+                Error::Smp(SmpError::InvalidFrame).to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn read_recording_lines() {
+    let recording = "# SMP recording\n\
+        \n\
+        0.000100 tx 0a0b\n\
+        1.500000 rx 0C0d\n\
+        2.000000 timeout\n\
+        3.000000 err BrokenPipe pipe closed\n\
+        4.000000 err SomethingNew\n";
+    let records = read_recording(recording.as_bytes()).unwrap();
+    assert_eq!(
+        records,
+        [
+            record(0.0001, Event::Sent(vec![0x0a, 0x0b])),
+            record(1.5, Event::Received(vec![0x0c, 0x0d])),
+            record(2.0, Event::Timeout),
+            record(
+                3.0,
+                Event::Error(io::ErrorKind::BrokenPipe, "pipe closed".into())
+            ),
+            record(4.0, Event::Error(io::ErrorKind::Other, "".into())),
+        ]
+    );
+
+    for invalid in [
+        "0.1 tx 0a0",
+        "0.1 tx zz",
+        "x tx 00",
+        "-1 rx 00",
+        "0.1 sent 00",
+        "0.1",
+    ] {
+        let err = read_recording(format!("# header\n{}\n", invalid).as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{}", invalid);
+        assert_eq!(err.to_string(), "invalid recording in line 2");
+    }
+}
+
+fn sequence(frame: &[u8]) -> u8 {
+    SmpHeader::parse(frame).unwrap().sequence()
+}
+
+#[test]
+fn replay_rewrites_sequence_numbers() {
+    let mut replay = ReplayTransport::new(vec![
+        record(0.0, Event::Sent(frame(OpCode::ReadRequest, 254, 1))),
+        record(0.1, Event::Received(frame(OpCode::ReadResponse, 254, 1))),
+        // a late reply to an earlier request
+        record(0.2, Event::Received(frame(OpCode::ReadResponse, 253, 2))),
+        record(0.3, Event::Sent(frame(OpCode::ReadRequest, 255, 3))),
+        record(0.4, Event::Received(frame(OpCode::ReadResponse, 255, 3))),
+        record(0.5, Event::Received(frame(OpCode::ReadResponse, 0, 4))),
+    ]);
+
+    replay.send(frame(OpCode::ReadRequest, 10, 1)).unwrap();
+    assert_eq!(sequence(&replay.receive().unwrap()), 10);
+    assert_eq!(sequence(&replay.receive().unwrap()), 9);
+
+    // the offset wraps around in both directions
+    replay.send(frame(OpCode::ReadRequest, 0, 3)).unwrap();
+    assert_eq!(sequence(&replay.receive().unwrap()), 0);
+    assert_eq!(sequence(&replay.receive().unwrap()), 1);
+}
+
+#[test]
+fn replay_errors() {
+    let mut replay = ReplayTransport::new(vec![
+        record(0.0, Event::Sent(frame(OpCode::ReadRequest, 0, 1))),
+        record(1.0, Event::Timeout),
+        record(
+            1.1,
+            Event::Error(io::ErrorKind::BrokenPipe, "closed".into()),
+        ),
+        record(1.2, Event::Sent(frame(OpCode::ReadRequest, 1, 2))),
+        record(1.3, Event::Received(frame(OpCode::ReadResponse, 1, 2))),
+        record(1.4, Event::Sent(frame(OpCode::ReadRequest, 2, 3))),
+    ]);
+
+    replay.send(frame(OpCode::ReadRequest, 0, 1)).unwrap();
+    assert!(matches!(replay.receive(), Err(Error::Timeout)));
+    assert!(
+        matches!(replay.receive(), Err(Error::Io(err)) if err.kind() == io::ErrorKind::BrokenPipe)
+    );
+    // the device didn't answer before the next request
+    assert!(matches!(replay.receive(), Err(Error::Timeout)));
+
+    // the skipped response isn't returned for the next request
+    replay.send(frame(OpCode::ReadRequest, 1, 2)).unwrap();
+    replay.send(frame(OpCode::ReadRequest, 2, 3)).unwrap();
+    assert!(
+        matches!(replay.receive(), Err(Error::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof)
+    );
+    assert!(
+        matches!(replay.send(vec![]), Err(Error::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof)
+    );
+}
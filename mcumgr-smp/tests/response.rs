@@ -0,0 +1,103 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Tests for decoding success and error payloads as [SmpResponse].
+
+#![cfg(feature = "payload-cbor")]
+
+use ciborium::{cbor, Value};
+use mcumgr_smp::codec::{CborCodec, DecodePayload, EncodePayload};
+use mcumgr_smp::error_codes::{OsMgmtError, ReturnCode};
+use mcumgr_smp::os_management::{EchoResponse, EchoResult, ResetResult};
+use mcumgr_smp::response::{ResponseError, SmpResponse};
+
+fn encode(value: Value) -> Vec<u8> {
+    let mut buf = Vec::new();
+    ciborium::into_writer(&value, &mut buf).unwrap();
+    buf
+}
+
+fn echo(value: Value) -> EchoResult {
+    CborCodec::decode_payload(&encode(value)).unwrap()
+}
+
+fn reset(value: Value) -> ResetResult {
+    CborCodec::decode_payload(&encode(value)).unwrap()
+}
+
+#[test]
+fn success() {
+    let response = echo(cbor!({ "r" => "hello" }).unwrap());
+    assert_eq!(response.into_result().unwrap().r, "hello");
+
+    // v1 devices may send `rc: 0` along with the payload
+    let response = echo(cbor!({ "r" => "hello", "rc" => 0 }).unwrap());
+    assert_eq!(response.into_result().unwrap().r, "hello");
+
+    assert!(reset(cbor!({}).unwrap()).is_ok());
+}
+
+#[test]
+fn v1_error() {
+    let response = echo(cbor!({ "rc" => 8 }).unwrap());
+    let err = response.into_result().unwrap_err();
+    assert_eq!(err, ResponseError::from(ReturnCode::NotSupported));
+    assert_eq!(err.return_code(), Some(ReturnCode::NotSupported));
+
+    let response = reset(cbor!({ "rc" => 1, "rsn" => "busy" }).unwrap());
+    assert_eq!(
+        response.into_result().unwrap_err(),
+        ResponseError::Rc {
+            rc: 1,
+            rsn: Some("busy".into())
+        }
+    );
+}
+
+#[test]
+fn v2_error() {
+    let response = echo(cbor!({ "err" => { "group" => 0, "rc" => 2 } }).unwrap());
+    let err = response.into_result().unwrap_err();
+    assert_eq!(err, ResponseError::from(OsMgmtError::InvalidFormat));
+    assert!(err.group_error().is_some());
+
+    // the error takes precedence over a v1 return code
+    let response = reset(cbor!({ "err" => { "group" => 64, "rc" => 5 }, "rc" => 0 }).unwrap());
+    assert_eq!(
+        response.into_result().unwrap_err(),
+        ResponseError::Group { group: 64, rc: 5 }
+    );
+
+    let malformed = encode(cbor!({ "err" => { "group" => "os" } }).unwrap());
+    assert!(<CborCodec as DecodePayload<ResetResult>>::decode_payload(&malformed).is_err());
+}
+
+#[test]
+fn unrelated_map() {
+    // `ResetResponse {}` is an empty struct and accepts any map ...
+    assert!(reset(cbor!({ "x" => 1 }).unwrap()).is_ok());
+    // ... but not one carrying an error
+    assert!(!reset(cbor!({ "x" => 1, "rc" => 3 }).unwrap()).is_ok());
+    assert!(!reset(cbor!({ "x" => 1, "err" => { "group" => 0, "rc" => 1 } }).unwrap()).is_ok());
+
+    // a payload missing the required fields fails to decode
+    let unrelated = encode(cbor!({ "x" => 1 }).unwrap());
+    assert!(<CborCodec as DecodePayload<EchoResult>>::decode_payload(&unrelated).is_err());
+}
+
+#[test]
+fn encode_roundtrip() {
+    let responses: [SmpResponse<EchoResponse>; 3] = [
+        SmpResponse::Ok(EchoResponse { r: "hi".into() }),
+        SmpResponse::Err(ReturnCode::Busy.into()),
+        SmpResponse::Err(OsMgmtError::InvalidFormat.into()),
+    ];
+    for response in responses {
+        let mut buf = Vec::new();
+        CborCodec::encode_payload(&response, &mut buf).unwrap();
+        let decoded: EchoResult = CborCodec::decode_payload(&buf).unwrap();
+        assert_eq!(
+            decoded.as_result().map(|r| &r.r),
+            response.as_result().map(|r| &r.r)
+        );
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Tests for the async serial transport against a PTY pair.
+
+#![cfg(all(unix, feature = "transport-serial-async", feature = "payload-cbor"))]
+
+use mcumgr_smp::os_management::{EchoRequest, EchoResponse};
+use mcumgr_smp::transport::console::Pacing;
+use mcumgr_smp::transport::error::Error;
+use mcumgr_smp::transport::serial::SerialTransportAsync;
+use mcumgr_smp::transport::smp::{CborSmpTransportAsync, SmpTransportAsync};
+use mcumgr_smp::transport::smp_framing::{SmpTransportDecoder, SmpTransportEncoder};
+use mcumgr_smp::{OpCode, SmpFrame};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::time::Instant;
+use tokio_serial::SerialStream;
+
+/// Encode `frame` with the console framing
+fn console_lines(frame: &[u8]) -> Vec<u8> {
+    let mut encoder = SmpTransportEncoder::new(frame);
+    let mut out = Vec::new();
+    let mut buf = [0; 128];
+    while !encoder.is_complete() {
+        let len = encoder.write_line(&mut buf).unwrap();
+        out.extend_from_slice(&buf[..len]);
+    }
+    out
+}
+
+/// Read a console framed frame from `device`, checking that no line exceeds `line_length`
+async fn read_frame(device: &mut BufReader<SerialStream>, line_length: usize) -> Vec<u8> {
+    let mut decoder = SmpTransportDecoder::new();
+    let mut line = Vec::new();
+    while !decoder.is_complete() {
+        line.clear();
+        device.read_until(b'\n', &mut line).await.unwrap();
+        assert!(line.len() <= line_length);
+        decoder.input_line(&line).unwrap();
+    }
+    decoder.into_frame_payload().unwrap()
+}
+
+/// Answer a single echo request on `device`
+async fn answer_echo(device: &mut BufReader<SerialStream>) {
+    let request = read_frame(device, 127).await;
+    let request: SmpFrame<EchoRequest> = SmpFrame::decode_with_cbor(&request).unwrap();
+    let response = SmpFrame {
+        operation: OpCode::WriteResponse,
+        version: request.version,
+        flags: 0,
+        group: request.group,
+        sequence: request.sequence,
+        command: request.command,
+        data: EchoResponse { r: request.data.d },
+    };
+    let response = response.encode_with_cbor().unwrap();
+    device
+        .get_mut()
+        .write_all(&console_lines(&response))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn echo() {
+    let (host, device) = SerialStream::pair().unwrap();
+    let mut device = BufReader::new(device);
+    let mut transport =
+        CborSmpTransportAsync::new(Box::new(SerialTransportAsync::from_stream(host)));
+
+    let (response, ()) = tokio::join!(
+        transport.request(EchoRequest { d: "hello".into() }),
+        answer_echo(&mut device),
+    );
+    assert_eq!(response.unwrap().r, "hello");
+}
+
+#[tokio::test]
+async fn timeout() {
+    let (host, _device) = SerialStream::pair().unwrap();
+    let mut transport = SerialTransportAsync::from_stream(host);
+    transport.recv_timeout(Some(Duration::from_millis(50)));
+
+    match transport.receive().await {
+        Err(Error::Timeout) => {}
+        res => panic!("expected a timeout, got {:?}", res),
+    }
+}
+
+#[tokio::test]
+async fn receive_continues_after_cancel() {
+    let (host, mut device) = SerialStream::pair().unwrap();
+    let mut transport = SerialTransportAsync::from_stream(host);
+
+    let frame = SmpFrame::new(
+        OpCode::ReadResponse,
+        1,
+        mcumgr_smp::Group::Default,
+        0,
+        vec![0xa0],
+    )
+    .encode::<mcumgr_smp::codec::RawCodec>()
+    .unwrap();
+    let lines = console_lines(&frame);
+    let (first, second) = lines.split_at(lines.len() / 2);
+
+    device.write_all(first).await.unwrap();
+    let cancelled = tokio::time::timeout(Duration::from_millis(50), transport.receive()).await;
+    assert!(cancelled.is_err());
+
+    device.write_all(second).await.unwrap();
+    assert_eq!(transport.receive().await.unwrap(), frame);
+}
+
+fn raw_frame(operation: OpCode, sequence: u8, data: Vec<u8>) -> Vec<u8> {
+    SmpFrame::new(operation, sequence, mcumgr_smp::Group::Default, 0, data)
+        .encode::<mcumgr_smp::codec::RawCodec>()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn line_length_and_delays() {
+    let (host, device) = SerialStream::pair().unwrap();
+    let mut device = BufReader::new(device);
+    let mut transport = SerialTransportAsync::from_stream(host);
+    transport.set_pacing(Pacing {
+        line_length: 32,
+        line_delay: Duration::from_millis(5),
+        frame_delay: Duration::from_millis(100),
+    });
+
+    // with the header, length and CRC these are 212 bytes, 11 lines of 21 bytes in base64
+    let request = raw_frame(OpCode::WriteRequest, 1, vec![0x55; 200]);
+    let start = Instant::now();
+    let (sent, received) =
+        tokio::join!(transport.send(request.clone()), read_frame(&mut device, 32));
+    sent.unwrap();
+    assert_eq!(received, request);
+    assert!(start.elapsed() >= Duration::from_millis(10 * 5));
+
+    let (sent, received) =
+        tokio::join!(transport.send(request.clone()), read_frame(&mut device, 32));
+    sent.unwrap();
+    assert_eq!(received, request);
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
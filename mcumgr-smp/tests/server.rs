@@ -0,0 +1,207 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Tests for request dispatching and error responses of [SmpServer].
+
+#![cfg(feature = "server")]
+
+use mcumgr_smp::error_codes::{ReturnCode, ShellMgmtError};
+use mcumgr_smp::os_management::{echo, reset, EchoResponse, ResetRequest, ResetResponse};
+use mcumgr_smp::response::{ResponseError, SmpResponse};
+use mcumgr_smp::server::{CustomHandler, HandlerResult, OsHandler, ShellHandler, SmpServer};
+use mcumgr_smp::shell_management::{shell_command, ShellCommand, ShellResponse};
+use mcumgr_smp::{Group, OpCode, SmpError, SmpFrame, SmpHeader, SmpVersion};
+use serde::de::DeserializeOwned;
+use std::sync::{Arc, Mutex};
+
+struct Os {
+    resets: Arc<Mutex<Vec<u8>>>,
+}
+
+impl OsHandler for Os {
+    fn reset(&mut self, request: ResetRequest) -> HandlerResult<ResetResponse> {
+        self.resets.lock().unwrap().push(request.force);
+        Ok(ResetResponse {})
+    }
+}
+
+struct Shell;
+
+impl ShellHandler for Shell {
+    fn execute(&mut self, request: ShellCommand) -> HandlerResult<ShellResponse> {
+        if request.argv.is_empty() {
+            return Err(ShellMgmtError::EmptyCommand.into());
+        }
+        Ok(ShellResponse {
+            o: request.argv.join(" "),
+            ret: 0,
+        })
+    }
+}
+
+/// Answers every command with its command id and payload length
+struct Custom;
+
+impl CustomHandler for Custom {
+    fn handle(&mut self, header: &SmpHeader) -> HandlerResult<Vec<u8>> {
+        Ok(vec![header.command(), header.payload().len() as u8])
+    }
+}
+
+fn server() -> (SmpServer, Arc<Mutex<Vec<u8>>>) {
+    let resets = Arc::new(Mutex::new(Vec::new()));
+    let server = SmpServer::new()
+        .with_os(Os {
+            resets: resets.clone(),
+        })
+        .with_shell(Shell)
+        .with_group(Group::Custom(64), Custom);
+    (server, resets)
+}
+
+fn process<T: DeserializeOwned>(
+    server: &mut SmpServer,
+    request: &[u8],
+) -> SmpFrame<SmpResponse<T>> {
+    let response = server.process(request).unwrap().unwrap();
+    SmpFrame::decode_with_cbor(&response).unwrap()
+}
+
+fn raw_request(operation: OpCode, group: Group, command: u8, payload: Vec<u8>) -> Vec<u8> {
+    SmpFrame::new(operation, 7, group, command, payload)
+        .encode::<mcumgr_smp::codec::RawCodec>()
+        .unwrap()
+}
+
+fn not_supported() -> ResponseError {
+    ReturnCode::NotSupported.into()
+}
+
+#[test]
+fn dispatch_by_group_and_command() {
+    let (mut server, resets) = server();
+
+    let request = echo(3, "hello".into()).encode_with_cbor().unwrap();
+    let response = process::<EchoResponse>(&mut server, &request);
+    assert_eq!(response.operation, OpCode::WriteResponse);
+    assert_eq!(
+        (response.group, response.sequence, response.command),
+        (Group::Default, 3, 0)
+    );
+    assert_eq!(response.data.into_result().unwrap().r, "hello");
+
+    let request = reset(4, true).encode_with_cbor().unwrap();
+    let response = process::<ResetResponse>(&mut server, &request);
+    assert!(response.data.is_ok());
+    assert_eq!(*resets.lock().unwrap(), [1]);
+
+    let request = shell_command(5, vec!["kernel".into(), "uptime".into()])
+        .encode_with_cbor()
+        .unwrap();
+    let response = process::<ShellResponse>(&mut server, &request);
+    assert_eq!(response.data.into_result().unwrap().o, "kernel uptime");
+
+    let request = raw_request(OpCode::ReadRequest, Group::Custom(64), 9, vec![1, 2, 3]);
+    let response = server.process(&request).unwrap().unwrap();
+    let response = SmpHeader::parse(&response).unwrap();
+    assert_eq!(response.operation(), OpCode::ReadResponse);
+    assert_eq!(response.payload(), [9, 3]);
+}
+
+#[test]
+fn unknown_group_or_command() {
+    let (mut server, _) = server();
+
+    // no handler for the group
+    let request = raw_request(
+        OpCode::ReadRequest,
+        Group::ApplicationManagement,
+        0,
+        vec![0xa0],
+    );
+    let response = process::<ResetResponse>(&mut server, &request);
+    assert_eq!(response.data.into_result().unwrap_err(), not_supported());
+
+    let request = raw_request(OpCode::ReadRequest, Group::Custom(100), 0, vec![0xa0]);
+    let response = process::<ResetResponse>(&mut server, &request);
+    assert_eq!(response.data.into_result().unwrap_err(), not_supported());
+
+    // unknown command, and a known command with the wrong operation
+    let request = raw_request(OpCode::WriteRequest, Group::Default, 42, vec![0xa0]);
+    let response = process::<ResetResponse>(&mut server, &request);
+    assert_eq!(response.data.into_result().unwrap_err(), not_supported());
+
+    let request = raw_request(OpCode::ReadRequest, Group::Default, 0, vec![0xa0]);
+    let response = process::<ResetResponse>(&mut server, &request);
+    assert_eq!(response.data.into_result().unwrap_err(), not_supported());
+
+    // a command the handler doesn't implement
+    let request = raw_request(OpCode::ReadRequest, Group::Default, 2, vec![0xa0]);
+    let response = process::<ResetResponse>(&mut server, &request);
+    assert_eq!(response.data.into_result().unwrap_err(), not_supported());
+}
+
+#[test]
+fn group_errors_by_version() {
+    let (mut server, _) = server();
+
+    let mut request = shell_command(1, vec![]);
+    let response = process::<ShellResponse>(&mut server, &request.encode_with_cbor().unwrap());
+    assert_eq!(response.version, SmpVersion::V2);
+    assert_eq!(
+        response.data.into_result().unwrap_err(),
+        ResponseError::Group { group: 9, rc: 3 }
+    );
+
+    // version 1 clients only understand `rc`
+    request.version = SmpVersion::V1;
+    let response = process::<ShellResponse>(&mut server, &request.encode_with_cbor().unwrap());
+    assert_eq!(response.version, SmpVersion::V1);
+    assert_eq!(
+        response.data.into_result().unwrap_err(),
+        ReturnCode::InvalidValue.into()
+    );
+}
+
+#[test]
+fn empty_and_malformed_payloads() {
+    let (mut server, resets) = server();
+
+    // no payload at all is treated as an empty map
+    let request = raw_request(OpCode::WriteRequest, Group::Default, 5, vec![]);
+    let response = process::<ResetResponse>(&mut server, &request);
+    assert!(response.data.is_ok());
+    assert_eq!(*resets.lock().unwrap(), [0]);
+
+    // an empty map is missing the required fields
+    let request = raw_request(OpCode::WriteRequest, Group::Default, 0, vec![0xa0]);
+    let response = process::<EchoResponse>(&mut server, &request);
+    assert_eq!(
+        response.data.into_result().unwrap_err(),
+        ReturnCode::InvalidValue.into()
+    );
+
+    let request = raw_request(OpCode::WriteRequest, Group::Default, 0, vec![0xff, 0x00]);
+    let response = process::<EchoResponse>(&mut server, &request);
+    assert_eq!(
+        response.data.into_result().unwrap_err(),
+        ReturnCode::InvalidValue.into()
+    );
+}
+
+#[test]
+fn invalid_frames() {
+    let (mut server, _) = server();
+
+    let request = echo(1, "hi".into()).encode_with_cbor().unwrap();
+    assert!(matches!(
+        server.process(&request[..6]),
+        Err(SmpError::InvalidFrame)
+    ));
+    // the length field doesn't match the payload
+    assert!(server.process(&request[..request.len() - 1]).is_err());
+
+    // responses are ignored
+    let mut response = request.clone();
+    response[0] = (response[0] & !0x07) | u8::from(OpCode::WriteResponse);
+    assert!(server.process(&response).unwrap().is_none());
+}
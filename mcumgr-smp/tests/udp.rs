@@ -0,0 +1,273 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Tests for the UDP transports over the loopback interface.
+
+#![cfg(all(feature = "transport-udp", feature = "transport-udp-async"))]
+
+use mcumgr_smp::codec::RawCodec;
+use mcumgr_smp::transport::error::Error;
+use mcumgr_smp::transport::smp::{SmpTransport, SmpTransportAsync};
+use mcumgr_smp::transport::udp::{AddressFamily, UdpTransport, UdpTransportAsync};
+use mcumgr_smp::{Group, OpCode, SmpFrame, SmpHeader};
+use std::net::{SocketAddr, UdpSocket};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+fn frame(operation: OpCode, sequence: u8) -> Vec<u8> {
+    sized_frame(operation, sequence, 9)
+}
+
+/// A frame of `len` bytes including the header
+fn sized_frame(operation: OpCode, sequence: u8, len: usize) -> Vec<u8> {
+    SmpFrame::new(operation, sequence, Group::Default, 0, vec![0xa0; len - 8])
+        .encode::<RawCodec>()
+        .unwrap()
+}
+
+/// A device answering one request with a response of `len` bytes, returns the host address
+fn spawn_sized_device(len: usize) -> (SocketAddr, JoinHandle<SocketAddr>) {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = socket.local_addr().unwrap();
+
+    let handle = std::thread::spawn(move || {
+        let mut buf = [0; 1500];
+        let (len_received, host) = socket.recv_from(&mut buf).unwrap();
+        let sequence = SmpHeader::parse(&buf[..len_received]).unwrap().sequence();
+        socket
+            .send_to(&sized_frame(OpCode::ReadResponse, sequence, len), host)
+            .unwrap();
+        host
+    });
+
+    (addr, handle)
+}
+
+/// A device on a lossy link: answers the requests it receives `copies[n]` times,
+/// and records the sequence numbers of all requests
+fn spawn_device(copies: Vec<usize>) -> (SocketAddr, JoinHandle<Vec<u8>>) {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = socket.local_addr().unwrap();
+
+    let handle = std::thread::spawn(move || {
+        let mut received = Vec::new();
+        let mut buf = [0; 1500];
+        for copies in copies {
+            let (len, host) = socket.recv_from(&mut buf).unwrap();
+            let sequence = SmpHeader::parse(&buf[..len]).unwrap().sequence();
+            received.push(sequence);
+            for _ in 0..copies {
+                socket
+                    .send_to(&frame(OpCode::ReadResponse, sequence), host)
+                    .unwrap();
+            }
+        }
+        received
+    });
+
+    (addr, handle)
+}
+
+#[test]
+fn retransmit_and_drop_duplicates() {
+    // the first request is lost, the retransmission is answered twice
+    let (addr, device) = spawn_device(vec![0, 2, 1]);
+    let mut transport = UdpTransport::new(addr).unwrap();
+    transport
+        .recv_timeout(Some(Duration::from_millis(100)))
+        .unwrap();
+    transport.set_retries(2);
+
+    transport.send(frame(OpCode::ReadRequest, 1)).unwrap();
+    assert_eq!(transport.receive().unwrap(), frame(OpCode::ReadResponse, 1));
+    transport.send(frame(OpCode::ReadRequest, 2)).unwrap();
+    assert_eq!(transport.receive().unwrap(), frame(OpCode::ReadResponse, 2));
+
+    assert_eq!(device.join().unwrap(), [1, 1, 2]);
+}
+
+#[test]
+fn timeout_after_retries() {
+    let (addr, device) = spawn_device(vec![0, 0, 0]);
+    let mut transport = UdpTransport::new(addr).unwrap();
+    transport
+        .recv_timeout(Some(Duration::from_millis(50)))
+        .unwrap();
+    transport.set_retries(2);
+
+    transport.send(frame(OpCode::ReadRequest, 7)).unwrap();
+    assert!(matches!(transport.receive(), Err(Error::Timeout)));
+    assert_eq!(device.join().unwrap(), [7, 7, 7]);
+}
+
+#[tokio::test]
+async fn retransmit_and_drop_duplicates_async() {
+    let (addr, device) = spawn_device(vec![0, 2, 1]);
+    let mut transport = UdpTransportAsync::new(addr).await.unwrap();
+    transport.recv_timeout(Some(Duration::from_millis(100)));
+    transport.set_retries(2);
+
+    transport.send(frame(OpCode::ReadRequest, 1)).await.unwrap();
+    assert_eq!(
+        transport.receive().await.unwrap(),
+        frame(OpCode::ReadResponse, 1)
+    );
+    transport.send(frame(OpCode::ReadRequest, 2)).await.unwrap();
+    assert_eq!(
+        transport.receive().await.unwrap(),
+        frame(OpCode::ReadResponse, 2)
+    );
+
+    assert_eq!(device.join().unwrap(), [1, 1, 2]);
+}
+
+#[tokio::test]
+async fn timeout_after_retries_async() {
+    let (addr, device) = spawn_device(vec![0, 0]);
+    let mut transport = UdpTransportAsync::new(addr).await.unwrap();
+    transport.recv_timeout(Some(Duration::from_millis(50)));
+    transport.set_retries(1);
+
+    transport.send(frame(OpCode::ReadRequest, 7)).await.unwrap();
+    assert!(matches!(transport.receive().await, Err(Error::Timeout)));
+    assert_eq!(device.join().unwrap(), [7, 7]);
+}
+
+#[test]
+fn truncated_response() {
+    let (addr, device) = spawn_sized_device(1501);
+    let mut transport = UdpTransport::new(addr).unwrap();
+
+    transport.send(frame(OpCode::ReadRequest, 1)).unwrap();
+    assert!(matches!(transport.receive(), Err(Error::Truncated(1500))));
+    device.join().unwrap();
+}
+
+#[test]
+fn response_fills_buffer() {
+    let (addr, device) = spawn_sized_device(1500);
+    let mut transport = UdpTransport::new(addr).unwrap();
+
+    transport.send(frame(OpCode::ReadRequest, 1)).unwrap();
+    assert_eq!(transport.receive().unwrap().len(), 1500);
+    device.join().unwrap();
+}
+
+#[tokio::test]
+async fn large_recv_buffer_async() {
+    let (addr, device) = spawn_sized_device(9000);
+    let mut transport = UdpTransportAsync::builder()
+        .recv_buffer_size(9000)
+        .connect_async(addr)
+        .await
+        .unwrap();
+
+    transport.send(frame(OpCode::ReadRequest, 1)).await.unwrap();
+    assert_eq!(transport.receive().await.unwrap().len(), 9000);
+    device.join().unwrap();
+}
+
+#[test]
+fn bind_address() {
+    let (addr, device) = spawn_sized_device(9);
+    let mut transport = UdpTransport::builder()
+        .bind("127.0.0.2:0".parse().unwrap())
+        .connect(addr)
+        .unwrap();
+
+    transport.send(frame(OpCode::ReadRequest, 1)).unwrap();
+    transport.receive().unwrap();
+    assert_eq!(device.join().unwrap().ip().to_string(), "127.0.0.2");
+}
+
+#[test]
+fn address_family() {
+    let (addr, device) = spawn_sized_device(9);
+
+    let err = UdpTransport::builder()
+        .family(AddressFamily::V6)
+        .connect(addr)
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::AddrNotAvailable);
+
+    let mut transport = UdpTransport::builder()
+        .family(AddressFamily::V4)
+        .connect(("localhost", addr.port()))
+        .unwrap();
+    transport.send(frame(OpCode::ReadRequest, 1)).unwrap();
+    transport.receive().unwrap();
+    device.join().unwrap();
+}
+
+#[test]
+fn zero_recv_buffer_size() {
+    let err = UdpTransport::builder()
+        .recv_buffer_size(0)
+        .connect("127.0.0.1:1337")
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn each_address_is_tried() {
+    let (addr, device) = spawn_sized_device(9);
+
+    // the IPv6 address can't be reached from the IPv4 bind address
+    let targets = [
+        SocketAddr::from(([0, 0, 0, 0, 0, 0, 0, 1], addr.port())),
+        addr,
+    ];
+    let mut transport = UdpTransport::builder()
+        .bind("127.0.0.1:0".parse().unwrap())
+        .connect(&targets[..])
+        .unwrap();
+    transport.send(frame(OpCode::ReadRequest, 1)).unwrap();
+    transport.receive().unwrap();
+    device.join().unwrap();
+
+    let err = UdpTransport::builder()
+        .bind("127.0.0.1:0".parse().unwrap())
+        .connect(&targets[..1])
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn v4_mapped_target() {
+    let mapped = |addr: SocketAddr| {
+        SocketAddr::new(
+            format!("::ffff:{}", addr.ip()).parse().unwrap(),
+            addr.port(),
+        )
+    };
+
+    // reached over IPv4
+    let (addr, device) = spawn_sized_device(9);
+    let mut transport = UdpTransport::builder()
+        .family(AddressFamily::V4)
+        .connect(mapped(addr))
+        .unwrap();
+    transport.send(frame(OpCode::ReadRequest, 1)).unwrap();
+    transport.receive().unwrap();
+    assert_eq!(device.join().unwrap().ip().to_string(), "127.0.0.1");
+
+    // over a dual-stack socket
+    let (addr, device) = spawn_sized_device(9);
+    let mut transport = UdpTransport::builder()
+        .bind("[::]:0".parse().unwrap())
+        .connect(mapped(addr))
+        .unwrap();
+    transport.send(frame(OpCode::ReadRequest, 1)).unwrap();
+    transport.receive().unwrap();
+    device.join().unwrap();
+
+    let err = UdpTransport::builder()
+        .bind("[::]:0".parse().unwrap())
+        .only_v6(true)
+        .connect(mapped(addr))
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
@@ -0,0 +1,155 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Tests for UDP discovery over the loopback interface.
+
+#![cfg(all(
+    feature = "transport-udp",
+    feature = "transport-udp-async",
+    feature = "payload-cbor"
+))]
+
+use mcumgr_smp::os_management::{EchoRequest, EchoResponse, GetInfoResponse};
+use mcumgr_smp::transport::udp::discovery::{discover, discover_async, IdentityError, Probe};
+use mcumgr_smp::transport::udp::UdpTransportBuilder;
+use mcumgr_smp::{OpCode, SmpFrame, SmpHeader};
+use serde::Serialize;
+use std::net::{SocketAddr, UdpSocket};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+fn response<T: Serialize>(request: &SmpHeader, sequence: u8, data: T) -> Vec<u8> {
+    SmpFrame::new(
+        OpCode::ReadResponse,
+        sequence,
+        request.group(),
+        request.command(),
+        data,
+    )
+    .encode_with_cbor()
+    .unwrap()
+}
+
+/// Answer one probe from two devices at 127.0.0.1 and 127.0.0.2, with a duplicate,
+/// a stale reply and some garbage in between. With `oversized`, a third device at 127.0.0.3
+/// answers `os info` with more than 1500 bytes.
+fn spawn_devices(oversized: bool) -> (SocketAddr, JoinHandle<()>) {
+    let listener = UdpSocket::bind("0.0.0.0:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let handle = std::thread::spawn(move || {
+        let mut buf = [0; 1500];
+        let (len, host) = listener.recv_from(&mut buf).unwrap();
+        let header = SmpHeader::parse(&buf[..len]).unwrap();
+        let sequence = header.sequence();
+
+        let reply = |name: &str| {
+            if header.command() == 0 {
+                let request: SmpFrame<EchoRequest> =
+                    SmpFrame::decode_with_cbor(&buf[..len]).unwrap();
+                response(&header, sequence, EchoResponse { r: request.data.d })
+            } else {
+                let output = format!("Zephyr {} 3.7.0", name);
+                response(&header, sequence, GetInfoResponse { output })
+            }
+        };
+
+        let first = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let second = UdpSocket::bind("127.0.0.2:0").unwrap();
+        first.send_to(b"not smp", host).unwrap();
+        let stale = response(
+            &header,
+            sequence.wrapping_sub(1),
+            EchoResponse { r: "".into() },
+        );
+        first.send_to(&stale, host).unwrap();
+        first.send_to(&reply("first"), host).unwrap();
+        second.send_to(&reply("second"), host).unwrap();
+        if oversized {
+            let third = UdpSocket::bind("127.0.0.3:0").unwrap();
+            third.send_to(&reply(&"x".repeat(1500)), host).unwrap();
+        }
+        first.send_to(&reply("again"), host).unwrap();
+    });
+
+    (SocketAddr::from(([127, 255, 255, 255], port)), handle)
+}
+
+#[test]
+fn discover_info() {
+    let (target, devices) = spawn_devices(false);
+
+    let found = discover(target, Probe::Info, Duration::from_millis(300)).unwrap();
+    devices.join().unwrap();
+
+    let found: Vec<_> = found
+        .into_iter()
+        .map(|r| (r.addr.ip().to_string(), r.identity.unwrap()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("127.0.0.1".into(), "Zephyr first 3.7.0".into()),
+            ("127.0.0.2".into(), "Zephyr second 3.7.0".into()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn discover_echo_async() {
+    let (target, devices) = spawn_devices(false);
+
+    let found = discover_async(target, Probe::Echo, Duration::from_millis(300))
+        .await
+        .unwrap();
+    devices.join().unwrap();
+
+    assert_eq!(found.len(), 2);
+    assert!(found.iter().all(|r| r.identity.is_ok()));
+}
+
+#[test]
+fn discover_with_builder_options() {
+    let (target, devices) = spawn_devices(false);
+
+    let found = UdpTransportBuilder::new()
+        .bind("127.0.0.1:0".parse().unwrap())
+        .discover(target, Probe::Info, Duration::from_millis(300))
+        .unwrap();
+    devices.join().unwrap();
+    assert_eq!(found.len(), 2);
+
+    // the responses don't fit, the garbage and the stale reply are still ignored
+    let (target, devices) = spawn_devices(false);
+    let found = UdpTransportBuilder::new()
+        .recv_buffer_size(16)
+        .discover(target, Probe::Info, Duration::from_millis(300))
+        .unwrap();
+    devices.join().unwrap();
+    assert_eq!(found.len(), 2);
+    assert!(found
+        .iter()
+        .all(|r| r.identity == Err(IdentityError::Truncated(16))));
+}
+
+#[tokio::test]
+async fn discover_oversized_responder() {
+    let (target, devices) = spawn_devices(true);
+
+    let found = discover_async(target, Probe::Info, Duration::from_millis(300))
+        .await
+        .unwrap();
+    devices.join().unwrap();
+
+    let found: Vec<_> = found
+        .into_iter()
+        .map(|r| (r.addr.ip().to_string(), r.identity))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("127.0.0.1".into(), Ok("Zephyr first 3.7.0".into())),
+            ("127.0.0.2".into(), Ok("Zephyr second 3.7.0".into())),
+            ("127.0.0.3".into(), Err(IdentityError::Truncated(1500))),
+        ]
+    );
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use mcumgr_smp::error_codes::{FsMgmtError, ReturnCode};
+use mcumgr_smp::fs_management::{
+    FileCloseRequest, FileCloseResponse, FileDownloadRequest, FileDownloadResponse,
+    FileStatusRequest, FileStatusResponse, FileUploadRequest, FileUploadResponse,
+};
+use mcumgr_smp::server::{FsHandler, HandlerResult};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Maximum number of bytes returned per download request
+const DOWNLOAD_CHUNK: u64 = 512;
+
+/// File system group backed by a directory, device paths are relative to it
+pub struct DirFs {
+    root: PathBuf,
+}
+
+impl DirFs {
+    pub fn open(root: PathBuf) -> io::Result<Self> {
+        fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    /// Map a device path to a path below the root, rejecting paths that would leave it
+    fn path(&self, name: &str) -> HandlerResult<PathBuf> {
+        let mut path = self.root.clone();
+        for component in Path::new(name).components() {
+            match component {
+                Component::RootDir => {}
+                Component::Normal(part) => path.push(part),
+                _ => return Err(FsMgmtError::FileInvalidName.into()),
+            }
+        }
+
+        if path == self.root {
+            return Err(FsMgmtError::FileInvalidName.into());
+        }
+        Ok(path)
+    }
+}
+
+fn open_error(err: io::Error) -> FsMgmtError {
+    match err.kind() {
+        io::ErrorKind::NotFound => FsMgmtError::FileNotFound,
+        _ => FsMgmtError::FileOpenFailed,
+    }
+}
+
+impl FsHandler for DirFs {
+    fn download(&mut self, request: FileDownloadRequest) -> HandlerResult<FileDownloadResponse> {
+        let path = self.path(&request.name)?;
+        if path.is_dir() {
+            return Err(FsMgmtError::FileIsDirectory.into());
+        }
+
+        let mut file = File::open(&path).map_err(open_error)?;
+        let len = file
+            .metadata()
+            .map_err(|_| FsMgmtError::FileReadFailed)?
+            .len();
+        if request.off > len {
+            return Err(FsMgmtError::FileOffsetLargerThanFile.into());
+        }
+
+        let mut data = Vec::new();
+        file.seek(SeekFrom::Start(request.off))
+            .map_err(|_| FsMgmtError::FileSeekFailed)?;
+        file.take(DOWNLOAD_CHUNK)
+            .read_to_end(&mut data)
+            .map_err(|_| FsMgmtError::FileReadFailed)?;
+
+        Ok(FileDownloadResponse {
+            off: request.off,
+            data,
+            len: (request.off == 0).then_some(len),
+        })
+    }
+
+    fn upload(&mut self, request: FileUploadRequest) -> HandlerResult<FileUploadResponse> {
+        let path = self.path(&request.name)?;
+        if path.is_dir() {
+            return Err(FsMgmtError::FileIsDirectory.into());
+        }
+
+        let mut file = if request.off == 0 {
+            if request.len.is_none() {
+                return Err(ReturnCode::InvalidValue.into());
+            }
+            File::create(&path).map_err(open_error)?
+        } else {
+            let file = OpenOptions::new()
+                .append(true)
+                .open(&path)
+                .map_err(open_error)?;
+            let len = file
+                .metadata()
+                .map_err(|_| FsMgmtError::FileReadFailed)?
+                .len();
+            if request.off != len {
+                return Err(FsMgmtError::FileOffsetNotValid.into());
+            }
+            file
+        };
+
+        file.write_all(&request.data)
+            .map_err(|_| FsMgmtError::FileWriteFailed)?;
+
+        Ok(FileUploadResponse {
+            off: request.off + request.data.len() as u64,
+        })
+    }
+
+    fn status(&mut self, request: FileStatusRequest) -> HandlerResult<FileStatusResponse> {
+        let metadata = fs::metadata(self.path(&request.name)?).map_err(open_error)?;
+        if metadata.is_dir() {
+            return Err(FsMgmtError::FileIsDirectory.into());
+        }
+        Ok(FileStatusResponse {
+            len: metadata.len(),
+        })
+    }
+
+    fn close(&mut self, _request: FileCloseRequest) -> HandlerResult<FileCloseResponse> {
+        // files are only kept open for the duration of a request
+        Ok(FileCloseResponse {})
+    }
+}
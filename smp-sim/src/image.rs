@@ -0,0 +1,297 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use mcumgr_smp::application_management::{
+    EraseRequest, EraseResponse, GetImageStatePayload, GetStatePayload, ImageState,
+    ImageUploadRequest, SetStatePayload, WriteImageChunkPayload,
+};
+use mcumgr_smp::error_codes::ImageMgmtError;
+use mcumgr_smp::server::{HandlerResult, ImageHandler};
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+const IMAGE_MAGIC: u32 = 0x96f3b83d;
+
+/// Swap state of the simulated bootloader, persisted in `boot-state`
+#[derive(Debug)]
+struct BootState {
+    /// the secondary slot will be swapped in on the next reset
+    pending: bool,
+    /// the pending image will be confirmed after the swap
+    permanent: bool,
+    /// the primary slot is confirmed and won't be reverted on reset
+    confirmed: bool,
+}
+
+struct Upload {
+    len: usize,
+    off: usize,
+    sha: Option<Vec<u8>>,
+}
+
+/// Two image slots stored as `slot0.bin` (primary, running) and `slot1.bin` (secondary).
+///
+/// Follows MCUboot's swap semantics: an image marked for test is swapped into the primary
+/// slot on reset and swapped back on the next reset unless it was confirmed.
+/// The image hash is the SHA-256 of the whole slot file.
+pub struct Images {
+    dir: PathBuf,
+    state: BootState,
+    upload: Option<Upload>,
+}
+
+impl Images {
+    pub fn open(dir: PathBuf) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+
+        let mut state = BootState {
+            pending: false,
+            permanent: false,
+            confirmed: true,
+        };
+        if let Ok(saved) = fs::read_to_string(dir.join("boot-state")) {
+            for line in saved.lines() {
+                match line.split_once('=') {
+                    Some(("pending", v)) => state.pending = v == "1",
+                    Some(("permanent", v)) => state.permanent = v == "1",
+                    Some(("confirmed", v)) => state.confirmed = v == "1",
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(Self {
+            dir,
+            state,
+            upload: None,
+        })
+    }
+
+    fn slot_path(&self, slot: usize) -> PathBuf {
+        self.dir.join(format!("slot{}.bin", slot))
+    }
+
+    fn save_state(&self) -> io::Result<()> {
+        let state = format!(
+            "pending={}\npermanent={}\nconfirmed={}\n",
+            self.state.pending as u8, self.state.permanent as u8, self.state.confirmed as u8
+        );
+        fs::write(self.dir.join("boot-state"), state)
+    }
+
+    /// Returns the version and hash of the image in `slot`, or `None` if the slot is empty
+    fn read_slot(&self, slot: usize) -> io::Result<Option<(String, Vec<u8>)>> {
+        let image = match fs::read(self.slot_path(slot)) {
+            Ok(image) if !image.is_empty() => image,
+            Ok(_) => return Ok(None),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        Ok(Some((
+            image_version(&image),
+            Sha256::digest(&image).to_vec(),
+        )))
+    }
+
+    fn image_states(&self) -> HandlerResult<GetImageStatePayload> {
+        let mut images = Vec::new();
+        for slot in 0..2 {
+            let Some((version, hash)) = self
+                .read_slot(slot)
+                .map_err(|_| ImageMgmtError::FlashReadFailed)?
+            else {
+                continue;
+            };
+
+            let primary = slot == 0;
+            images.push(ImageState {
+                image: Some(0),
+                slot: slot as i32,
+                version,
+                hash,
+                bootable: true,
+                pending: !primary && self.state.pending,
+                confirmed: primary && self.state.confirmed,
+                active: primary,
+                permanent: !primary && self.state.permanent,
+            });
+        }
+
+        Ok(GetImageStatePayload {
+            images,
+            split_status: None,
+        })
+    }
+
+    /// Simulate a reset of the device: swap in a pending image or revert an unconfirmed one
+    pub fn reset(&mut self) -> io::Result<()> {
+        self.upload = None;
+
+        if self.state.pending {
+            info!("swapping in pending image");
+            self.swap_slots()?;
+            self.state.confirmed = self.state.permanent;
+            self.state.pending = false;
+            self.state.permanent = false;
+        } else if !self.state.confirmed {
+            info!("reverting unconfirmed image");
+            self.swap_slots()?;
+            self.state.confirmed = true;
+        }
+
+        self.save_state()
+    }
+
+    fn swap_slots(&self) -> io::Result<()> {
+        let tmp = self.dir.join("swap.bin");
+        let rename = |from: &PathBuf, to: &PathBuf| match fs::rename(from, to) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            res => res,
+        };
+
+        rename(&self.slot_path(0), &tmp)?;
+        rename(&self.slot_path(1), &self.slot_path(0))?;
+        rename(&tmp, &self.slot_path(1))
+    }
+}
+
+/// Formats the version of an MCUboot image header, `0.0.0` for images without header
+fn image_version(image: &[u8]) -> String {
+    let Some(header) = image.get(..28) else {
+        return "0.0.0".to_string();
+    };
+    if u32::from_le_bytes([header[0], header[1], header[2], header[3]]) != IMAGE_MAGIC {
+        return "0.0.0".to_string();
+    }
+
+    let (major, minor) = (header[20], header[21]);
+    let revision = u16::from_le_bytes([header[22], header[23]]);
+    let build = u32::from_le_bytes([header[24], header[25], header[26], header[27]]);
+    if build == 0 {
+        format!("{}.{}.{}", major, minor, revision)
+    } else {
+        format!("{}.{}.{}.{}", major, minor, revision, build)
+    }
+}
+
+/// Image group handler, shares the slots with the OS handler which resets the device
+pub struct ImageGroup(pub Arc<Mutex<Images>>);
+
+impl ImageHandler for ImageGroup {
+    fn state(&mut self, _request: GetStatePayload) -> HandlerResult<GetImageStatePayload> {
+        self.0.lock().unwrap().image_states()
+    }
+
+    fn set_state(&mut self, request: SetStatePayload) -> HandlerResult<GetImageStatePayload> {
+        let mut images = self.0.lock().unwrap();
+        let hash_of = |slot| {
+            images
+                .read_slot(slot)
+                .map(|image| image.map(|(_, hash)| hash))
+                .map_err(|_| ImageMgmtError::FlashReadFailed)
+        };
+        let primary = hash_of(0)?;
+        let secondary = hash_of(1)?;
+
+        if request.hash.is_empty() || Some(&request.hash) == primary.as_ref() {
+            if !request.confirm {
+                return Err(ImageMgmtError::ImageSettingTestToActiveDenied.into());
+            }
+            images.state.confirmed = true;
+        } else if Some(&request.hash) == secondary.as_ref() {
+            images.state.pending = true;
+            images.state.permanent = request.confirm;
+        } else {
+            return Err(ImageMgmtError::HashNotFound.into());
+        }
+
+        images
+            .save_state()
+            .map_err(|_| ImageMgmtError::FlashWriteFailed)?;
+        images.image_states()
+    }
+
+    fn upload(&mut self, request: ImageUploadRequest) -> HandlerResult<WriteImageChunkPayload> {
+        let mut images = self.0.lock().unwrap();
+
+        if request.image.unwrap_or(0) != 0 {
+            return Err(ImageMgmtError::InvalidSlot.into());
+        }
+
+        if request.off == 0 {
+            let len = request.len.ok_or(ImageMgmtError::InvalidLength)?;
+            if images.state.pending {
+                return Err(ImageMgmtError::ImageAlreadyPending.into());
+            }
+            fs::File::create(images.slot_path(1)).map_err(|_| ImageMgmtError::FlashEraseFailed)?;
+            images.upload = Some(Upload {
+                len,
+                off: 0,
+                sha: request.sha,
+            });
+        }
+
+        let path = images.slot_path(1);
+        let upload = images
+            .upload
+            .as_mut()
+            .ok_or(ImageMgmtError::InvalidOffset)?;
+
+        // the client resumes from the offset in the response
+        if request.off != upload.off {
+            return Ok(WriteImageChunkPayload {
+                off: upload.off as u32,
+                match_: None,
+            });
+        }
+        if upload.off + request.data.len() > upload.len {
+            return Err(ImageMgmtError::InvalidImageDataOverrun.into());
+        }
+
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(&request.data))
+            .map_err(|_| ImageMgmtError::FlashWriteFailed)?;
+        upload.off += request.data.len();
+
+        let off = upload.off as u32;
+        let mut match_ = None;
+        if upload.off == upload.len {
+            let sha = upload.sha.take();
+            images.upload = None;
+
+            let (_, hash) = images
+                .read_slot(1)
+                .map_err(|_| ImageMgmtError::FlashReadFailed)?
+                .ok_or(ImageMgmtError::NoImage)?;
+            info!("image upload complete, {} bytes", off);
+            match_ = sha.map(|sha| sha == hash);
+        }
+
+        Ok(WriteImageChunkPayload { off, match_ })
+    }
+
+    fn erase(&mut self, request: EraseRequest) -> HandlerResult<EraseResponse> {
+        let mut images = self.0.lock().unwrap();
+
+        if request.slot.unwrap_or(1) != 1 {
+            return Err(ImageMgmtError::InvalidSlot.into());
+        }
+        if images.state.pending {
+            return Err(ImageMgmtError::ImageAlreadyPending.into());
+        }
+
+        images.upload = None;
+        match fs::remove_file(images.slot_path(1)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => {
+                Err(ImageMgmtError::FlashEraseFailed.into())
+            }
+            _ => Ok(EraseResponse {}),
+        }
+    }
+}
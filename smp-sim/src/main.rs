@@ -0,0 +1,133 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use std::error::Error;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use clap::{ArgGroup, Parser};
+use mcumgr_smp::server::SmpServer;
+use tracing::error;
+use tracing_subscriber::prelude::*;
+
+/// directory backed file system group
+pub mod fs;
+/// image slots with MCUboot swap semantics
+pub mod image;
+/// OS and shell groups
+pub mod os;
+/// settings store
+pub mod settings;
+/// statistics counters
+pub mod stats;
+/// UDP, Unix socket and PTY transports, the latter only on Unix
+pub mod transport;
+
+#[derive(Parser, Debug)]
+#[command(
+    author,
+    version,
+    about = "Simulates an mcumgr device (Zephyr + MCUboot) for testing SMP clients.",
+    before_help = "Copyright (c) 2026 Gessler GmbH.",
+    help_template = "{about-with-newline}\nAuthor: {author-with-newline}{before-help}{usage-heading} {usage}\n\n{all-args}"
+)]
+#[cfg_attr(
+    unix,
+    command(group(ArgGroup::new("transport").required(true).multiple(true).args(["udp", "pty", "unix"])))
+)]
+#[cfg_attr(
+    not(unix),
+    command(group(ArgGroup::new("transport").required(true).args(["udp"])))
+)]
+struct Cli {
+    /// Directory for the image slots, the file system and the settings
+    #[arg(short, long, default_value = "smp-sim")]
+    state_dir: PathBuf,
+
+    /// Serve SMP over UDP
+    #[arg(long, num_args = 0..=1, default_missing_value = "[::]:1337")]
+    udp: Option<SocketAddr>,
+
+    /// Serve SMP console framing on a pseudo terminal
+    #[cfg(unix)]
+    #[arg(long)]
+    pty: bool,
+
+    /// Create a symlink to the pseudo terminal
+    #[cfg(unix)]
+    #[arg(long, requires = "pty")]
+    pty_link: Option<PathBuf>,
+
+    /// Serve SMP console framing on a Unix stream socket
+    #[cfg(unix)]
+    #[arg(long)]
+    unix: Option<PathBuf>,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let cli = Cli::parse();
+
+    let stats = Arc::new(stats::Stats::default());
+    let images = Arc::new(Mutex::new(image::Images::open(cli.state_dir.join("img"))?));
+    let boot_time = Arc::new(Mutex::new(Instant::now()));
+
+    let reset = os::Reset {
+        images: images.clone(),
+        stats: stats.clone(),
+        boot_time: boot_time.clone(),
+        pending: Arc::new(AtomicBool::new(false)),
+    };
+
+    let server = SmpServer::new()
+        .with_os(os::Os {
+            reset: reset.clone(),
+        })
+        .with_image(image::ImageGroup(images))
+        .with_stats(stats::StatsGroup(stats.clone()))
+        .with_fs(fs::DirFs::open(cli.state_dir.join("fs"))?)
+        .with_shell(os::Shell { boot_time })
+        .with_settings(settings::SettingsStore::open(
+            cli.state_dir.join("settings"),
+        )?);
+
+    let device = transport::Device {
+        server: Arc::new(Mutex::new(server)),
+        stats,
+        reset,
+    };
+
+    let mut threads = Vec::new();
+    if let Some(addr) = cli.udp {
+        let device = device.clone();
+        threads.push(thread::spawn(move || transport::serve_udp(device, addr)));
+    }
+    #[cfg(unix)]
+    if let Some(path) = cli.unix {
+        let device = device.clone();
+        threads.push(thread::spawn(move || transport::serve_unix(device, &path)));
+    }
+    #[cfg(unix)]
+    if cli.pty {
+        let link = cli.pty_link;
+        threads.push(thread::spawn(move || {
+            transport::serve_pty(device, link.as_deref())
+        }));
+    }
+
+    for thread in threads {
+        if let Err(err) = thread.join().expect("transport thread panicked") {
+            error!("transport failed: {}", err);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,118 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::image::Images;
+use crate::stats::Stats;
+use mcumgr_smp::error_codes::{OsMgmtError, ShellMgmtError};
+use mcumgr_smp::os_management::{
+    GetInfoRequest, GetInfoResponse, McumgrParamsRequest, McumgrParamsResponse, ResetRequest,
+    ResetResponse,
+};
+use mcumgr_smp::server::{HandlerResult, OsHandler, ShellHandler};
+use mcumgr_smp::shell_management::{ShellCommand, ShellResponse};
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::info;
+
+/// SMP buffer size reported to clients
+pub const BUF_SIZE: u32 = 2048;
+
+/// Resets requested by clients, performed once the response is sent
+#[derive(Clone)]
+pub struct Reset {
+    pub images: Arc<Mutex<Images>>,
+    pub stats: Arc<Stats>,
+    pub boot_time: Arc<Mutex<Instant>>,
+    pub pending: Arc<AtomicBool>,
+}
+
+impl Reset {
+    /// Simulate the reset of the device if one was requested
+    pub fn perform_pending(&self) -> io::Result<()> {
+        if !self.pending.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        info!("simulating reset");
+        *self.boot_time.lock().unwrap() = Instant::now();
+        self.stats.resets.fetch_add(1, Ordering::Relaxed);
+        self.images.lock().unwrap().reset()
+    }
+}
+
+/// OS group: echo, reset, info and buffer parameters
+pub struct Os {
+    pub reset: Reset,
+}
+
+impl OsHandler for Os {
+    fn reset(&mut self, _request: ResetRequest) -> HandlerResult<ResetResponse> {
+        self.reset.pending.store(true, Ordering::Relaxed);
+        Ok(ResetResponse {})
+    }
+
+    /// Answers like `uname`, see Zephyr's `os_mgmt_info`
+    fn info(&mut self, request: GetInfoRequest) -> HandlerResult<GetInfoResponse> {
+        let format = match request.format.as_str() {
+            "" => "s",
+            "a" => "snrvmpio",
+            format => format,
+        };
+
+        let mut output = Vec::new();
+        for c in format.chars() {
+            output.push(match c {
+                's' => "Zephyr",
+                'n' => "smp-sim",
+                'r' => "3.7.0",
+                'v' => env!("CARGO_PKG_VERSION"),
+                'm' => "sim",
+                'p' => "sim",
+                'i' => "native_sim",
+                'o' => "Zephyr",
+                _ => return Err(OsMgmtError::InvalidFormat.into()),
+            });
+        }
+
+        Ok(GetInfoResponse {
+            output: output.join(" "),
+        })
+    }
+
+    fn mcumgr_params(
+        &mut self,
+        _request: McumgrParamsRequest,
+    ) -> HandlerResult<McumgrParamsResponse> {
+        Ok(McumgrParamsResponse {
+            buf_size: BUF_SIZE,
+            buf_count: 4,
+        })
+    }
+}
+
+/// Shell group with a few built-in commands
+pub struct Shell {
+    pub boot_time: Arc<Mutex<Instant>>,
+}
+
+impl ShellHandler for Shell {
+    fn execute(&mut self, request: ShellCommand) -> HandlerResult<ShellResponse> {
+        let Some((command, args)) = request.argv.split_first() else {
+            return Err(ShellMgmtError::EmptyCommand.into());
+        };
+
+        let (o, ret) = match command.as_str() {
+            "echo" => (args.join(" "), 0),
+            "uptime" => {
+                let uptime = self.boot_time.lock().unwrap().elapsed();
+                (format!("Uptime: {} ms", uptime.as_millis()), 0)
+            }
+            "help" => ("Available commands: echo, help, uptime".to_string(), 0),
+            // -ENOEXEC, as returned by the Zephyr shell
+            _ => (format!("{}: command not found", command), -8),
+        };
+
+        Ok(ShellResponse { o, ret })
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use mcumgr_smp::error_codes::{ReturnCode, SettingsMgmtError};
+use mcumgr_smp::server::{HandlerResult, SettingsHandler};
+use mcumgr_smp::settings_management::{
+    CommitSettingsRequest, DeleteSettingRequest, LoadSettingsRequest, ReadSettingRequest,
+    ReadSettingResponse, SaveSettingsRequest, SettingsResponse, WriteSettingRequest,
+};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Maximum length of a setting name, as with Zephyr's default `SETTINGS_MAX_NAME_LEN`
+const MAX_NAME_LEN: usize = 64;
+
+/// Settings kept in memory, `save` writes them to a file and `load` reads them back.
+/// The file has one `name=hex value` line per setting.
+pub struct SettingsStore {
+    path: PathBuf,
+    values: BTreeMap<String, Vec<u8>>,
+}
+
+impl SettingsStore {
+    pub fn open(path: PathBuf) -> io::Result<Self> {
+        let mut store = Self {
+            path,
+            values: BTreeMap::new(),
+        };
+        match store.read_file() {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(store),
+        }
+    }
+
+    fn read_file(&mut self) -> io::Result<()> {
+        let content = fs::read_to_string(&self.path)?;
+        self.values = content
+            .lines()
+            .filter_map(|line| {
+                let (name, value) = line.split_once('=')?;
+                Some((name.to_string(), decode_hex(value)?))
+            })
+            .collect();
+        Ok(())
+    }
+
+    fn write_file(&self) -> io::Result<()> {
+        let mut content = String::new();
+        for (name, value) in &self.values {
+            content.push_str(name);
+            content.push('=');
+            for byte in value {
+                let _ = write!(content, "{:02x}", byte);
+            }
+            content.push('\n');
+        }
+        fs::write(&self.path, content)
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+impl SettingsHandler for SettingsStore {
+    fn read(&mut self, request: ReadSettingRequest) -> HandlerResult<ReadSettingResponse> {
+        let mut val = self
+            .values
+            .get(&request.name)
+            .cloned()
+            .ok_or(SettingsMgmtError::KeyNotFound)?;
+
+        let max_size = match request.max_size {
+            Some(max_size) if val.len() > max_size as usize => {
+                val.truncate(max_size as usize);
+                Some(max_size)
+            }
+            _ => None,
+        };
+        Ok(ReadSettingResponse { val, max_size })
+    }
+
+    fn write(&mut self, request: WriteSettingRequest) -> HandlerResult<SettingsResponse> {
+        if request.name.len() > MAX_NAME_LEN {
+            return Err(SettingsMgmtError::KeyTooLong.into());
+        }
+        if request.name.is_empty() || request.name.contains(['=', '\n']) {
+            return Err(ReturnCode::InvalidValue.into());
+        }
+        self.values.insert(request.name, request.val);
+        Ok(SettingsResponse {})
+    }
+
+    fn delete(&mut self, request: DeleteSettingRequest) -> HandlerResult<SettingsResponse> {
+        self.values
+            .remove(&request.name)
+            .ok_or(SettingsMgmtError::KeyNotFound)?;
+        Ok(SettingsResponse {})
+    }
+
+    fn commit(&mut self, _request: CommitSettingsRequest) -> HandlerResult<SettingsResponse> {
+        Ok(SettingsResponse {})
+    }
+
+    fn load(&mut self, _request: LoadSettingsRequest) -> HandlerResult<SettingsResponse> {
+        match self.read_file() {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(ReturnCode::Unknown.into()),
+            _ => Ok(SettingsResponse {}),
+        }
+    }
+
+    fn save(&mut self, _request: SaveSettingsRequest) -> HandlerResult<SettingsResponse> {
+        self.write_file().map_err(|_| ReturnCode::Unknown)?;
+        Ok(SettingsResponse {})
+    }
+}
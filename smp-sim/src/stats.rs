@@ -0,0 +1,58 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use mcumgr_smp::error_codes::StatsMgmtError;
+use mcumgr_smp::server::{HandlerResult, StatsHandler};
+use mcumgr_smp::stats_management::{
+    StatsListRequest, StatsListResponse, StatsReadRequest, StatsReadResponse,
+};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Counters updated by the transports and the OS group
+#[derive(Default)]
+pub struct Stats {
+    pub rx_frames: AtomicU64,
+    pub tx_frames: AtomicU64,
+    pub rx_bytes: AtomicU64,
+    pub tx_bytes: AtomicU64,
+    pub errors: AtomicU64,
+    pub resets: AtomicU64,
+}
+
+impl Stats {
+    fn fields(&self) -> BTreeMap<String, u64> {
+        [
+            ("rx_frames", &self.rx_frames),
+            ("tx_frames", &self.tx_frames),
+            ("rx_bytes", &self.rx_bytes),
+            ("tx_bytes", &self.tx_bytes),
+            ("errors", &self.errors),
+            ("resets", &self.resets),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.load(Ordering::Relaxed)))
+        .collect()
+    }
+}
+
+/// Statistics group with a single `smp` group
+pub struct StatsGroup(pub Arc<Stats>);
+
+impl StatsHandler for StatsGroup {
+    fn read(&mut self, request: StatsReadRequest) -> HandlerResult<StatsReadResponse> {
+        if request.name != "smp" {
+            return Err(StatsMgmtError::InvalidGroup.into());
+        }
+        Ok(StatsReadResponse {
+            name: request.name,
+            fields: self.0.fields(),
+        })
+    }
+
+    fn list(&mut self, _request: StatsListRequest) -> HandlerResult<StatsListResponse> {
+        Ok(StatsListResponse {
+            stat_list: vec!["smp".to_string()],
+        })
+    }
+}
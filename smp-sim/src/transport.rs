@@ -0,0 +1,196 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+use crate::os::{Reset, BUF_SIZE};
+use crate::stats::Stats;
+use mcumgr_smp::server::SmpServer;
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info, warn};
+
+#[cfg(unix)]
+use mcumgr_smp::transport::smp_framing::{ConsoleInput, SmpTransportDecoder, SmpTransportEncoder};
+#[cfg(unix)]
+use nix::pty::openpty;
+#[cfg(unix)]
+use nix::sys::termios::{cfmakeraw, tcgetattr, tcsetattr, SetArg};
+#[cfg(unix)]
+use nix::unistd::ttyname;
+#[cfg(unix)]
+use std::fs::File;
+#[cfg(unix)]
+use std::io::{BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::fd::FromRawFd;
+#[cfg(unix)]
+use std::os::unix::net::UnixListener;
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::thread;
+
+/// The server and counters shared by all transports
+#[derive(Clone)]
+pub struct Device {
+    pub server: Arc<Mutex<SmpServer>>,
+    pub stats: Arc<Stats>,
+    pub reset: Reset,
+}
+
+impl Device {
+    /// Process a request frame and return the response frame, if any
+    fn process(&self, request: &[u8]) -> Option<Vec<u8>> {
+        self.stats.rx_frames.fetch_add(1, Ordering::Relaxed);
+        self.stats
+            .rx_bytes
+            .fetch_add(request.len() as u64, Ordering::Relaxed);
+
+        let response = match self.server.lock().unwrap().process(request) {
+            Ok(response) => response?,
+            Err(err) => {
+                warn!("dropping invalid frame: {}", err);
+                self.stats.errors.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+        };
+
+        self.stats.tx_frames.fetch_add(1, Ordering::Relaxed);
+        self.stats
+            .tx_bytes
+            .fetch_add(response.len() as u64, Ordering::Relaxed);
+        Some(response)
+    }
+
+    /// Called once a response is sent, the device resets after answering a reset request
+    fn response_sent(&self) {
+        if let Err(err) = self.reset.perform_pending() {
+            warn!("reset failed: {}", err);
+        }
+    }
+}
+
+/// Answer SMP requests sent as UDP datagrams, like Zephyr's UDP transport
+pub fn serve_udp(device: Device, addr: SocketAddr) -> io::Result<()> {
+    let socket = UdpSocket::bind(addr)?;
+    info!("UDP: listening on {}", socket.local_addr()?);
+
+    // receive datagrams of any size, larger frames than the device buffer are dropped below
+    let mut buf = vec![0; u16::MAX as usize];
+    loop {
+        let (len, peer) = socket.recv_from(&mut buf)?;
+        debug!("UDP: {} bytes from {}", len, peer);
+        if len > BUF_SIZE as usize {
+            warn!(
+                "UDP: dropping {} byte frame, larger than the {} byte buffer",
+                len, BUF_SIZE
+            );
+            device.stats.errors.fetch_add(1, Ordering::Relaxed);
+            continue;
+        }
+        if let Some(response) = device.process(&buf[..len]) {
+            socket.send_to(&response, peer)?;
+            device.response_sent();
+        }
+    }
+}
+
+/// Answer SMP requests on a Unix stream socket, using the console framing
+#[cfg(unix)]
+pub fn serve_unix(device: Device, path: &Path) -> io::Result<()> {
+    // remove a stale socket of an earlier run
+    let _ = std::fs::remove_file(path);
+    let listener = UnixListener::bind(path)?;
+    info!("Unix socket: listening on {}", path.display());
+
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let device = device.clone();
+        thread::spawn(move || {
+            debug!("Unix socket: client connected");
+            let reader = BufReader::new(stream.try_clone()?);
+            let res = serve_console(device, reader, stream);
+            debug!("Unix socket: client disconnected: {:?}", res);
+            res
+        });
+    }
+    Ok(())
+}
+
+/// Answer SMP requests on a pseudo terminal, using the console framing.
+/// The terminal is linked to `link` if given.
+#[cfg(unix)]
+pub fn serve_pty(device: Device, link: Option<&Path>) -> io::Result<()> {
+    let pty = openpty(None, None)?;
+    // SAFETY: openpty returns newly opened file descriptors that nothing else owns
+    let (master, slave) = unsafe { (File::from_raw_fd(pty.master), File::from_raw_fd(pty.slave)) };
+
+    // pass the framing through unmodified
+    let mut termios = tcgetattr(pty.slave)?;
+    cfmakeraw(&mut termios);
+    tcsetattr(pty.slave, SetArg::TCSANOW, &termios)?;
+
+    let path: PathBuf = ttyname(pty.slave)?;
+    if let Some(link) = link {
+        let _ = std::fs::remove_file(link);
+        std::os::unix::fs::symlink(&path, link)?;
+        info!("PTY: {} -> {}", link.display(), path.display());
+    } else {
+        info!("PTY: {}", path.display());
+    }
+
+    // keep the slave open, otherwise reading the master fails while no client is connected
+    let _slave = slave;
+    let reader = BufReader::new(master.try_clone()?);
+    serve_console(device, reader, master)
+}
+
+#[cfg(unix)]
+fn serve_console(
+    device: Device,
+    mut reader: impl BufRead,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let mut decoder = SmpTransportDecoder::new();
+    let mut out = [0; 128];
+
+    loop {
+        let frame = match decoder.next_input() {
+            Some(Ok(ConsoleInput::Frame(frame))) => frame,
+            Some(Ok(ConsoleInput::Text(text))) => {
+                debug!("console: {}", String::from_utf8_lossy(&text));
+                continue;
+            }
+            Some(Err(err)) => {
+                debug!("console: dropping invalid frame: {}", err);
+                device.stats.errors.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+            None => {
+                let data = reader.fill_buf()?;
+                if data.is_empty() {
+                    return Ok(());
+                }
+                let len = data.len();
+                decoder.push(data);
+                reader.consume(len);
+                continue;
+            }
+        };
+
+        // invalid frames are counted by `process`, responses are ignored
+        let Some(response) = device.process(&frame) else {
+            continue;
+        };
+
+        let mut encoder = SmpTransportEncoder::new(&response);
+        while !encoder.is_complete() {
+            let len = encoder
+                .write_line(&mut out)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            writer.write_all(&out[..len])?;
+        }
+        writer.flush()?;
+        device.response_sent();
+    }
+}
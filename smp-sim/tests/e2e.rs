@@ -0,0 +1,166 @@
+// Copyright (c) 2026 Gessler GmbH.
+
+//! Runs the simulator and updates its image over UDP, like a client updating a device.
+
+use mcumgr_smp::application_management::{
+    GetImageStatePayload, GetStatePayload, ImageWriter, SetStatePayload,
+};
+use mcumgr_smp::os_management::{EchoRequest, ResetRequest};
+use mcumgr_smp::stats_management::StatsReadRequest;
+use mcumgr_smp::transport::smp::CborSmpTransport;
+use mcumgr_smp::transport::udp::UdpTransport;
+use sha2::{Digest, Sha256};
+use std::net::{SocketAddr, UdpSocket};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+/// The simulator process and its state directory, both removed on drop
+struct Sim {
+    process: Child,
+    state_dir: PathBuf,
+    addr: SocketAddr,
+}
+
+impl Sim {
+    fn start() -> Self {
+        // a free port, released again for the simulator
+        let addr = UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let state_dir = std::env::temp_dir().join(format!("smp-sim-e2e-{}", addr.port()));
+        let _ = std::fs::remove_dir_all(&state_dir);
+
+        let process = Command::new(env!("CARGO_BIN_EXE_smp-sim"))
+            .arg("--state-dir")
+            .arg(&state_dir)
+            .arg("--udp")
+            .arg(addr.to_string())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+
+        Self {
+            process,
+            state_dir,
+            addr,
+        }
+    }
+
+    /// Connect once the simulator answers
+    fn connect(&self) -> CborSmpTransport {
+        let mut udp = UdpTransport::new(self.addr).unwrap();
+        udp.recv_timeout(Some(Duration::from_millis(100))).unwrap();
+        let mut transport = CborSmpTransport::new(Box::new(udp));
+
+        for _ in 0..50 {
+            let echo = transport.request(EchoRequest { d: "ping".into() });
+            if echo.is_ok_and(|echo| echo.r == "ping") {
+                return transport;
+            }
+            // the port is unreachable until the simulator is listening
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        panic!("simulator didn't answer");
+    }
+}
+
+impl Drop for Sim {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+        let _ = std::fs::remove_dir_all(&self.state_dir);
+    }
+}
+
+/// An image with an MCUboot header of version 1.2.3
+fn image() -> Vec<u8> {
+    let mut image: Vec<u8> = (0..3000).map(|i| i as u8).collect();
+    image[..4].copy_from_slice(&0x96f3b83du32.to_le_bytes());
+    image[20..28].copy_from_slice(&[1, 2, 3, 0, 0, 0, 0, 0]);
+    image
+}
+
+fn state(transport: &mut CborSmpTransport) -> GetImageStatePayload {
+    transport.request(GetStatePayload {}).unwrap()
+}
+
+#[test]
+fn upload_test_reset_confirm() {
+    let sim = Sim::start();
+    let mut transport = sim.connect();
+
+    let original = state(&mut transport);
+    assert!(original.images.is_empty());
+
+    // upload
+    let image = image();
+    let hash = Sha256::digest(&image).to_vec();
+    let mut writer = ImageWriter::new(None, image.len(), Some(&hash), false);
+    let mut verified = None;
+    while writer.offset < image.len() {
+        let end = (writer.offset + 512).min(image.len());
+        let chunk = writer.write_chunk(&image[writer.offset..end]).data;
+        let response = transport.request(chunk).unwrap();
+        writer.offset = response.off as usize;
+        verified = response.match_;
+    }
+    assert_eq!(verified, Some(true));
+
+    let uploaded = state(&mut transport);
+    assert_eq!(uploaded.images.len(), 1);
+    let secondary = &uploaded.images[0];
+    assert_eq!((secondary.slot, secondary.version.as_str()), (1, "1.2.3"));
+    assert_eq!(secondary.hash, hash);
+    assert!(!secondary.pending);
+
+    // test
+    let tested = transport
+        .request(SetStatePayload {
+            hash: hash.clone(),
+            confirm: false,
+        })
+        .unwrap();
+    assert!(tested.images[0].pending && !tested.images[0].permanent);
+
+    // reset, the image is swapped in but not confirmed
+    transport.request(ResetRequest { force: 0 }).unwrap();
+    let booted = state(&mut transport);
+    let primary = &booted.images[0];
+    assert_eq!((primary.slot, &primary.hash), (0, &hash));
+    assert!(primary.active && !primary.confirmed);
+
+    // confirm, the image stays after the next reset
+    let confirmed = transport
+        .request(SetStatePayload {
+            hash: vec![],
+            confirm: true,
+        })
+        .unwrap();
+    assert!(confirmed.images[0].confirmed);
+
+    transport.request(ResetRequest { force: 0 }).unwrap();
+    let rebooted = state(&mut transport);
+    let primary = &rebooted.images[0];
+    assert_eq!((primary.slot, &primary.hash), (0, &hash));
+    assert!(primary.confirmed);
+}
+
+#[test]
+fn oversized_and_invalid_frames() {
+    let sim = Sim::start();
+    let mut transport = sim.connect();
+
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    // larger than the SMP buffer, and not a frame at all
+    socket.send_to(&[0; 4096], sim.addr).unwrap();
+    socket.send_to(&[0xff; 4], sim.addr).unwrap();
+
+    let stats = transport
+        .request(StatsReadRequest { name: "smp".into() })
+        .unwrap();
+    assert_eq!(stats.fields["errors"], 2);
+    assert_eq!(stats.fields["resets"], 0);
+}
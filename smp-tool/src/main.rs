@@ -6,20 +6,27 @@ use std::error::Error;
 use std::path::PathBuf;
 use std::time::Duration;
 
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use mcumgr_smp::{
-    application_management::{self, GetImageStateResult, WriteImageChunkResult},
-    os_management::{self, EchoResult},
-    shell_management::{self, ShellResult},
-    smp::SmpFrame,
+    application_management::GetStatePayload,
+    os_management::EchoRequest,
+    shell_management::ShellCommand,
     transport::{
         ble::BleTransport,
-        serial::SerialTransport,
-        smp::{CborSmpTransport, CborSmpTransportAsync},
-        udp::UdpTransportAsync,
+        console::Pacing,
+        error::Error as TransportError,
+        pcapng::{CaptureLink, PcapngTransport},
+        record::RecordingTransport,
+        serial::SerialTransportAsync,
+        smp::{CborSmpTransportAsync, SmpTransportAsync},
+        udp::{
+            discovery::{discover_async, Probe},
+            UdpTransportAsync,
+        },
     },
 };
 use sha2::Digest;
+use tokio::net::lookup_host;
 use tracing::debug;
 use tracing_subscriber::prelude::*;
 
@@ -42,8 +49,9 @@ pub enum Transport {
     help_template = "{about-with-newline}\nAuthor: {author-with-newline}{before-help}{usage-heading} {usage}\n\n{all-args}"
 )]
 struct Cli {
+    /// Transport to the device, required by all commands except `discover`
     #[arg(short, long, value_enum)]
-    transport: Transport,
+    transport: Option<Transport>,
 
     #[arg(short, long, required_if_eq("transport", "serial"))]
     serial_device: Option<String>,
@@ -51,24 +59,52 @@ struct Cli {
     #[arg(short = 'b', long, default_value_t = 115200)]
     serial_baud: u32,
 
+    /// Maximum length of the lines sent over serial, should match the device's
+    /// CONFIG_UART_MCUMGR_RX_BUF_SIZE
+    #[arg(long, default_value_t = 127)]
+    line_length: usize,
+
+    /// Pause between the lines of a frame sent over serial
+    #[arg(long, default_value_t = 0)]
+    line_delay_ms: u64,
+
+    /// Minimum time between two frames sent over serial
+    #[arg(long, default_value_t = 0)]
+    frame_delay_ms: u64,
+
     #[arg(short = 'd', long, required_if_eq("transport", "udp"))]
     dest_host: Option<String>,
 
     #[arg(short = 'p', long, default_value_t = 1337)]
     udp_port: u16,
 
+    /// Number of times a UDP request is sent again if there's no response within the timeout
+    #[arg(long, default_value_t = 3)]
+    udp_retries: usize,
+
     #[arg(long, default_value_t = 5000)]
     timeout_ms: u64,
 
     #[arg(short, long, required_if_eq("transport", "ble"))]
     name: Option<String>,
 
+    /// Record all sent and received frames to a file
+    #[arg(long)]
+    record: Option<PathBuf>,
+
+    /// Capture all sent and received frames to a pcapng file, e.g. for Wireshark
+    #[arg(long)]
+    capture: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
+    /// Find devices on the network
+    #[command(subcommand)]
+    Discover(DiscoverCmd),
     /// Send a command in the os group
     #[command(subcommand)]
     Os(OsCmd),
@@ -80,6 +116,30 @@ enum Commands {
     App(ApplicationCmd),
 }
 
+#[derive(Subcommand, Debug)]
+enum DiscoverCmd {
+    /// Send a request to a broadcast or multicast address and list the devices that answer
+    Udp {
+        /// Broadcast or multicast address, e.g. 255.255.255.255 or ff02::1%eth0
+        #[arg(default_value = "255.255.255.255")]
+        address: String,
+        /// Request sent to the devices
+        #[arg(long, value_enum, default_value_t = DiscoveryProbe::Info)]
+        probe: DiscoveryProbe,
+        /// How long to wait for responses
+        #[arg(long, default_value_t = 2000)]
+        window_ms: u64,
+    },
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug)]
+enum DiscoveryProbe {
+    /// os echo
+    Echo,
+    /// os info
+    Info,
+}
+
 #[derive(Subcommand, Debug)]
 enum OsCmd {
     /// Send an SMP Echo request
@@ -115,21 +175,58 @@ enum ApplicationCmd {
     },
 }
 
-pub enum UsedTransport {
-    SyncTransport(CborSmpTransport),
-    AsyncTransport(CborSmpTransportAsync),
+/// Wrap the transport in a recorder if requested
+fn record<T: SmpTransportAsync + Send + 'static>(
+    transport: T,
+    path: &Option<PathBuf>,
+) -> Result<Box<dyn SmpTransportAsync>, std::io::Error> {
+    Ok(match path {
+        Some(path) => Box::new(RecordingTransport::create(transport, path)?),
+        None => Box::new(transport),
+    })
 }
 
-impl UsedTransport {
-    pub async fn transceive_cbor<Req: serde::Serialize, Resp: serde::de::DeserializeOwned>(
-        &mut self,
-        frame: &SmpFrame<Req>,
-    ) -> Result<SmpFrame<Resp>, mcumgr_smp::transport::error::Error> {
-        match self {
-            UsedTransport::SyncTransport(ref mut t) => t.transceive_cbor(frame, false),
-            UsedTransport::AsyncTransport(ref mut t) => t.transceive_cbor(frame, false).await,
+/// Wrap the transport in the requested recorders
+fn wrap<T: SmpTransportAsync + Send + 'static>(
+    transport: T,
+    link: CaptureLink,
+    cli: &Cli,
+) -> Result<Box<dyn SmpTransportAsync>, std::io::Error> {
+    match &cli.capture {
+        Some(path) => record(PcapngTransport::create(transport, path, link)?, &cli.record),
+        None => record(transport, &cli.record),
+    }
+}
+
+/// List the devices answering a discovery request
+async fn discover(cmd: &DiscoverCmd, cli: &Cli) -> Result<(), Box<dyn Error>> {
+    let DiscoverCmd::Udp {
+        address,
+        probe,
+        window_ms,
+    } = cmd;
+
+    let target = lookup_host((address.as_str(), cli.udp_port))
+        .await?
+        .next()
+        .ok_or("address not found")?;
+    let probe = match probe {
+        DiscoveryProbe::Echo => Probe::Echo,
+        DiscoveryProbe::Info => Probe::Info,
+    };
+    debug!("discovering devices at {}", target);
+
+    let responders = discover_async(target, probe, Duration::from_millis(*window_ms)).await?;
+    for responder in &responders {
+        match &responder.identity {
+            Ok(identity) => println!("{}\t{}", responder.addr, identity),
+            Err(err) => println!("{}\t{}", responder.addr, err),
         }
     }
+    if responders.is_empty() {
+        eprintln!("no devices found");
+    }
+    Ok(())
 }
 
 #[tokio::main(flavor = "current_thread")]
@@ -141,74 +238,88 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     let cli: Cli = Cli::parse();
 
-    let mut transport = match cli.transport {
+    if let Commands::Discover(cmd) = &cli.command {
+        return discover(cmd, &cli).await;
+    }
+    let Some(transport) = cli.transport else {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::MissingRequiredArgument,
+                "--transport is required",
+            )
+            .exit();
+    };
+
+    let mut transport = match transport {
         Transport::Serial => {
-            let mut t = SerialTransport::new(
-                cli.serial_device.expect("serial device required"),
+            let mut t = SerialTransportAsync::new(
+                cli.serial_device.clone().expect("serial device required"),
                 cli.serial_baud,
             )?;
-            t.recv_timeout(Some(Duration::from_millis(cli.timeout_ms)))?;
-            UsedTransport::SyncTransport(CborSmpTransport {
-                transport: Box::new(t),
-            })
+            t.recv_timeout(Some(Duration::from_millis(cli.timeout_ms)));
+            t.set_pacing(Pacing {
+                line_length: cli.line_length,
+                line_delay: Duration::from_millis(cli.line_delay_ms),
+                frame_delay: Duration::from_millis(cli.frame_delay_ms),
+            });
+            t.on_console_output(|text| debug!("console: {}", String::from_utf8_lossy(text)));
+            CborSmpTransportAsync::new(wrap(t, CaptureLink::Serial, &cli)?)
         }
         Transport::Udp => {
-            let host = cli.dest_host.expect("dest_host required");
+            let host = cli.dest_host.clone().expect("dest_host required");
             let port = cli.udp_port;
 
             debug!("connecting to {} at port {}", host, port);
 
-            UsedTransport::AsyncTransport(CborSmpTransportAsync {
-                transport: Box::new(UdpTransportAsync::new((host, port)).await?),
-            })
+            let mut t = UdpTransportAsync::new((host, port)).await?;
+            t.recv_timeout(Some(Duration::from_millis(cli.timeout_ms)));
+            t.set_retries(cli.udp_retries);
+            CborSmpTransportAsync::new(wrap(t, CaptureLink::Udp, &cli)?)
         }
         Transport::Ble => {
             let adapters = BleTransport::adapters().await?;
             debug!("found {} adapter(s): {:?}:", adapters.len(), adapters);
             let adapter = adapters.first().ok_or("BLE adapters not found")?;
             debug!("selecting first adapter: {:?}:", adapter);
-            UsedTransport::AsyncTransport(CborSmpTransportAsync {
-                transport: Box::new(
-                    BleTransport::new(
-                        cli.name.unwrap(),
-                        adapter,
-                        Duration::from_millis(cli.timeout_ms),
-                    )
-                    .await?,
-                ),
-            })
+            let mut t = BleTransport::new(
+                cli.name.clone().unwrap(),
+                adapter,
+                Duration::from_millis(cli.timeout_ms),
+            )
+            .await?;
+            t.recv_timeout(Some(Duration::from_millis(cli.timeout_ms)));
+            CborSmpTransportAsync::new(wrap(t, CaptureLink::Ble, &cli)?)
         }
     };
 
     match cli.command {
+        Commands::Discover(_) => unreachable!("handled before connecting"),
         Commands::Os(OsCmd::Echo { msg }) => {
-            let ret: SmpFrame<EchoResult> = transport
-                .transceive_cbor(&os_management::echo(42, msg))
-                .await?;
+            let ret = transport.request(EchoRequest { d: msg }).await;
             debug!("{:?}", ret);
 
-            match ret.data {
-                EchoResult::Ok { r } => {
-                    println!("{}", r);
+            match ret {
+                Ok(payload) => {
+                    println!("{}", payload.r);
                 }
-                EchoResult::Err { rc } => {
-                    eprintln!("rc: {}", rc);
+                Err(TransportError::Device(err)) => {
+                    eprintln!("{}", err);
                 }
+                Err(err) => Err(err)?,
             }
         }
         Commands::Shell(ShellCmd::Exec { cmd }) => {
-            let ret: SmpFrame<ShellResult> = transport
-                .transceive_cbor(&shell_management::shell_command(42, cmd))
-                .await?;
+            let ret = transport.request(ShellCommand { argv: cmd }).await;
             debug!("{:?}", ret);
 
-            match ret.data {
-                ShellResult::Ok { o, ret } => {
-                    println!("ret: {}, o: {}", ret, o);
+            match ret {
+                Ok(payload) => {
+                    println!("ret: {}, o: {}", payload.ret, payload.o);
                 }
-                ShellResult::Err { rc } => {
-                    eprintln!("rc: {}", rc);
+                Err(TransportError::Device(err)) => {
+                    eprintln!("{}", err);
                 }
+                Err(err) => Err(err)?,
             }
         }
         Commands::Shell(ShellCmd::Interactive) => {
@@ -242,19 +353,18 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 println!("writing {}/{}", offset, firmware.len());
                 let chunk = &firmware[offset..min(firmware.len(), offset + chunk_size)];
 
-                let resp_frame: SmpFrame<WriteImageChunkResult> = transport
-                    .transceive_cbor(&updater.write_chunk(chunk))
-                    .await?;
+                let ret = transport.request(updater.write_chunk(chunk).data).await;
 
-                match resp_frame.data {
-                    WriteImageChunkResult::Ok(payload) => {
+                match ret {
+                    Ok(payload) => {
                         offset = payload.off as usize;
                         updater.offset = offset;
                         verified = payload.match_;
                     }
-                    WriteImageChunkResult::Err(err) => {
-                        Err(format!("Err from MCU: {:?}", err))?;
+                    Err(TransportError::Device(err)) => {
+                        Err(format!("Err from MCU: {}", err))?;
                     }
+                    Err(err) => Err(err)?,
                 }
             }
 
@@ -269,21 +379,17 @@ async fn main() -> Result<(), Box<dyn Error>> {
             }
         }
         Commands::App(ApplicationCmd::Info) => {
-            let ret: SmpFrame<GetImageStateResult> = transport
-                .transceive_cbor(&application_management::get_state(42))
-                .await?;
+            let ret = transport.request(GetStatePayload {}).await;
             debug!("{:?}", ret);
 
-            match ret.data {
-                GetImageStateResult::Ok(payload) => {
+            match ret {
+                Ok(payload) => {
                     println!("{:?}", payload)
                 }
-                GetImageStateResult::Err(err) => {
-                    eprintln!("rc: {}", err.rc);
-                    if let Some(msg) = err.rsn {
-                        eprintln!("rsn: {:?}", msg);
-                    }
+                Err(TransportError::Device(err)) => {
+                    eprintln!("{}", err);
                 }
+                Err(err) => Err(err)?,
             }
         }
     }
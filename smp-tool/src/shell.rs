@@ -9,13 +9,11 @@ use reedline::{
 use tracing::debug;
 
 use mcumgr_smp::{
-    shell_management::{self, ShellResult},
-    smp::SmpFrame,
+    shell_management::ShellCommand,
+    transport::{error::Error as TransportError, smp::CborSmpTransportAsync},
 };
 
-use crate::UsedTransport;
-
-pub async fn shell(transport: &mut UsedTransport) -> Result<(), Box<dyn Error>> {
+pub async fn shell(transport: &mut CborSmpTransportAsync) -> Result<(), Box<dyn Error>> {
     let keybindings = default_emacs_keybindings();
     let edit_mode = Box::new(Emacs::new(keybindings));
 
@@ -33,26 +31,20 @@ pub async fn shell(transport: &mut UsedTransport) -> Result<(), Box<dyn Error>>
             Signal::Success(buffer) => 'succ: {
                 let argv: Vec<_> = buffer.split_whitespace().map(|s| s.to_owned()).collect();
 
-                let ret: Result<SmpFrame<ShellResult>, _> = transport
-                    .transceive_cbor(&shell_management::shell_command(42, argv))
-                    .await;
+                let ret = transport.request(ShellCommand { argv }).await;
                 debug!("{:?}", ret);
 
-                let data = match ret {
-                    Ok(smp_frame) => smp_frame.data,
+                match ret {
+                    Ok(payload) => {
+                        println!("{}", payload.o);
+                    }
+                    Err(TransportError::Device(err)) => {
+                        eprintln!("SMP Error: {}", err);
+                    }
                     Err(err) => {
                         println!("transport error: {}", err);
                         break 'succ;
                     }
-                };
-
-                match data {
-                    ShellResult::Ok { o, ret: _ } => {
-                        println!("{}", o);
-                    }
-                    ShellResult::Err { rc } => {
-                        eprintln!("SMP Error: rc: {}", rc);
-                    }
                 }
             }
             Signal::CtrlD | Signal::CtrlC => {